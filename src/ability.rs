@@ -0,0 +1,72 @@
+/// This library holds the result of the ability searches: the pokemon having the ability and the
+/// slots it's in, a pokemon can have the ability as its first, second or hidden ability
+use std::fmt;
+
+use crate::{aligned_table, AbilitySlot, Pokemon};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AbilityHolder {
+    pub pokemon: Pokemon,
+    pub slots: Vec<AbilitySlot>,
+}
+
+impl AbilityHolder {
+    pub fn new(pokemon: Pokemon, ability: &str) -> Self {
+        let slots = pokemon.abilities.slots(ability);
+        AbilityHolder { pokemon, slots }
+    }
+
+    /// "Ability 1" or, when the ability is in several slots, "Ability 1, Hidden ability"
+    pub fn slot_names(&self) -> String {
+        self.slots.iter().map(|s| s.to_string()).collect::<Vec<String>>().join(", ")
+    }
+}
+
+impl fmt::Display for AbilityHolder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\nSlot: {}", self.pokemon, self.slot_names())
+    }
+}
+
+/// One line for every pokemon with the ability, the columns are aligned:
+/// "001  Bulbasaur  Grass/Poison  Hidden ability"
+pub fn holders_table(holders: &[AbilityHolder]) -> String {
+    let mut rows = vec![["Dex", "Species", "Type", "Slot"].map(String::from).to_vec()];
+    for holder in holders {
+        rows.push(vec![
+            format!("{:03}", holder.pokemon.dex_num),
+            holder.pokemon.species.clone(),
+            holder.pokemon.get_typing().to_string(),
+            holder.slot_names(),
+        ]);
+    }
+    aligned_table(&rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_pokedex, parser::test_fixtures::*, Abilities};
+
+    #[test]
+    fn ability_slots() {
+        let example = format!("{}\n026-Raichu-A\nStats: 60 HP/85 Atk/50 Def/95 SpA/85 SpD/110 Spe/485 BST\nType: Electric/Psychic\nAbilities: Surge Surfer\nLocation:\n* Evolve Pikachu (Thunder Stone)\nLevel Up:\n1: Thunder Shock\nTMs:\nTM06: Toxic\nEgg Moves:\n", BULBASAUR);
+        let (_, pokedex) = parse_pokedex(&example).unwrap();
+        let (bulbasaur, raichu) = (&pokedex[0], &pokedex[1]);
+
+        assert_eq!(bulbasaur.abilities.hidden.as_deref(), Some("Grassy Surge"));
+        assert_eq!(raichu.abilities.first, "Surge Surfer");
+        assert_eq!(raichu.abilities.hidden, None);
+        assert_eq!(raichu.abilities.to_string(), "Surge Surfer");
+        let stored: Abilities = serde_json::from_str(r#"["Chlorophyll", "Overgrow", "Grassy Surge"]"#).unwrap();
+        assert_eq!(stored, bulbasaur.abilities);
+
+        let holder = AbilityHolder::new(bulbasaur.clone(), "grassy surge");
+        assert_eq!(holder.slots, [AbilitySlot::Hidden]);
+        assert!(AbilityHolder::new(raichu.clone(), "Overgrow").slots.is_empty());
+        assert_eq!(
+            holders_table(&[holder]),
+            "Dex  Species    Type          Slot\n001  Bulbasaur  Grass/Poison  Hidden ability"
+        );
+    }
+}
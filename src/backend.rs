@@ -1,104 +1,525 @@
-use std::{env, fs};
+use std::{fmt, fs, path::Path, sync::Arc, collections::HashMap};
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use async_trait::async_trait;
 use egui_extras::{image::RetainedImage};
-use surrealdb::{Surreal, engine::local::{Db, File}};
+use surrealdb::{Surreal, sql::Thing, engine::local::{Db, File, Mem}};
 
-use crate::{Pokemon, parser::*};
+use crate::{canonical_species, learned_moves, normalize, DEFAULT_VERSION, Move, MoveCategory, MoveDetails, MoveMatch, MoveRegistry, Pokemon, PokemonType, load_move_details, PokemonQuery, PokedexStore, Storage, parser::*, evolution::*};
 
-static DB: Surreal<Db> = Surreal::init();
+/// Pokedex stored in SurrealDB, the evolutions are kept as "evolves_to" relations between the records.
+/// Every release is kept in the same table, the records are tagged with their version
+#[derive(Clone)]
+pub struct SurrealStore {
+    db: Surreal<Db>,
+    version: String,
+}
+
+impl SurrealStore {
+    pub async fn connect(storage: &Storage) -> Result<Self> {
+        let db = match storage {
+            Storage::File(path) => {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                Surreal::new::<File>(path.display().to_string()).await?
+            }
+            Storage::Memory => Surreal::new::<Mem>(()).await?,
+        };
+        db.use_ns("Luminescent").use_db("Pokedex").await?;
+
+        Ok(SurrealStore {
+            db,
+            version: DEFAULT_VERSION.to_string(),
+        })
+    }
 
-pub async fn connec_to_db() -> Result<()> {
-    DB.connect::<File>("temp.db").await?;
-    DB.use_ns("Luminescent").use_db("Pokedex").await?;
+    /// The same store looking at another release
+    pub fn for_version(mut self, version: &str) -> Self {
+        self.version = version.to_string();
+        self
+    }
+
+    /// Imports the pokedex files as the release of the store, every pokemon is stored under its
+    /// "record_id" so importing again updates the records in place. The records of pokemon no longer
+    /// in the dex are deleted, except the ones whose entry could not be parsed
+    pub async fn store_pokedex(&self, data_dir: &Path) -> Result<ImportSummary> {
+        let (mut pokedex, skipped) = load_pokedex(data_dir)?;
+        for pokemon in pokedex.iter_mut() {
+            pokemon.version = self.version.clone();
+        }
+
+        if !skipped.is_empty() {
+            println!("Skipped {} entries that could not be parsed:", skipped.len());
+            for error in &skipped {
+                println!("{}", error);
+            }
+        }
+        let skipped_species: Vec<String> = skipped
+            .iter()
+            .filter_map(|error| error.species.as_deref())
+            .map(|species| canonical_species(species).to_lowercase())
+            .collect();
+
+        let mut summary = ImportSummary::default();
+        let mut stored: HashMap<Thing, Pokemon> = self
+            .stored_pokemon()
+            .await?
+            .into_iter()
+            .map(|stored| (stored.id, stored.pokemon))
+            .collect();
+
+        // the record of each species is kept to relate the evolutions once every pokemon is stored
+        let evolutions = evolutions(&pokedex);
+        let mut records: HashMap<String, Thing> = HashMap::new();
+
+        for pokemon in pokedex {
+            let id = Thing::from(("pokemon", pokemon.record_id().as_str()));
+            records.insert(pokemon.species.clone(), id.clone());
+            match stored.remove(&id) {
+                Some(old) if old == pokemon => continue,
+                Some(_) => summary.changed.push(pokemon.species.clone()),
+                None => summary.added.push(pokemon.species.clone()),
+            }
+            self.upsert(&id, pokemon).await?;
+        }
+
+        for (id, pokemon) in stored {
+            if skipped_species.contains(&pokemon.species.to_lowercase()) {
+                records.insert(pokemon.species, id);
+                continue;
+            }
+            self.db.query("DELETE $id").bind(("id", &id)).await?;
+            summary.removed.push(pokemon.species);
+        }
+        summary.removed.sort();
+
+        self.db
+            .query("DELETE evolves_to WHERE in.version = $version")
+            .bind(("version", &self.version))
+            .await?;
+        for evolution in evolutions {
+            let (Some(from), Some(to)) = (records.get(&evolution.from), records.get(&evolution.to)) else {
+                summary.unrelated.push(evolution.to_string());
+                continue;
+            };
+            self.db.query("RELATE $from->evolves_to->$to SET method = $method, condition = $condition")
+                .bind(("from", from))
+                .bind(("to", to))
+                .bind(("method", &evolution.method))
+                .bind(("condition", &evolution.condition))
+                .await?;
+        }
+
+        // the move details are optional, they are replaced by the ones of the data directory
+        let move_details = load_move_details(data_dir)?;
+        self.db
+            .query("DELETE move_details WHERE version = $version")
+            .bind(("version", &self.version))
+            .await?;
+        summary.move_details = move_details.len();
+        for details in move_details {
+            self.insert_move_details(details).await?;
+        }
+
+        Ok(summary)
+    }
+
+    /// Finds the pokemon by its display name, ignoring case
+    async fn find_species(&self, species: &str) -> Result<Option<Pokemon>> {
+        let mut response = self
+            .db
+            .query(
+                r#"
+            SELECT
+                version, dex_num, species, base_species, form, pokemon_type, new_pokemon_type,
+                stats, new_stats, abilities, held_items, lvl_up_moves, tms, egg_moves, locations
+            FROM pokemon
+            WHERE version = $version AND string::lowercase(species) = string::lowercase($species)
+            "#,
+            )
+            .bind(("version", &self.version))
+            .bind(("species", species))
+            .await?;
+        let pokemon: Option<Pokemon> = response.take(0)?;
+        Ok(pokemon)
+    }
+
+    /// Creates the record or replaces its content when it already exists
+    async fn upsert(&self, id: &Thing, pokemon: Pokemon) -> Result<()> {
+        self.db
+            .query("UPDATE $id CONTENT $pokemon")
+            .bind(("id", id))
+            .bind(("pokemon", pokemon))
+            .await?;
+        Ok(())
+    }
 
-    Ok(())
+    /// Every stored pokemon with the id of its record
+    async fn stored_pokemon(&self) -> Result<Vec<StoredPokemon>> {
+        let mut response = self
+            .db
+            .query(
+                r#"
+            SELECT
+                id, version, dex_num, species, base_species, form, pokemon_type, new_pokemon_type,
+                stats, new_stats, abilities, held_items, lvl_up_moves, tms, egg_moves, locations
+            FROM pokemon
+            WHERE version = $version
+            "#,
+            )
+            .bind(("version", &self.version))
+            .await?;
+        let stored: Vec<StoredPokemon> = response.take(0)?;
+        Ok(stored)
+    }
 }
 
-pub async fn find_pokemon(species: &str) -> Result<Option<Pokemon>> {
-    let mut response = DB
-        .query(
+#[async_trait]
+impl PokedexStore for SurrealStore {
+    fn version(&self) -> &str {
+        &self.version
+    }
+
+    fn with_version(&self, version: &str) -> Arc<dyn PokedexStore> {
+        Arc::new(self.clone().for_version(version))
+    }
+
+    async fn versions(&self) -> Result<Vec<String>> {
+        let mut response = self
+            .db
+            .query("SELECT version FROM pokemon GROUP BY version")
+            .await?;
+        let versions: Vec<VersionRecord> = response.take(0)?;
+        let mut versions: Vec<String> = versions.into_iter().map(|v| v.version).collect();
+        versions.sort();
+
+        Ok(versions)
+    }
+
+    async fn all(&self) -> Result<Vec<Pokemon>> {
+        let mut response = self
+            .db
+            .query(
+                r#"
+            SELECT
+                version, dex_num, species, base_species, form, pokemon_type, new_pokemon_type,
+                stats, new_stats, abilities, held_items, lvl_up_moves, tms, egg_moves, locations
+            FROM pokemon
+            WHERE version = $version
+            ORDER BY dex_num ASC
+            "#,
+            )
+            .bind(("version", &self.version))
+            .await?;
+        let pokemons: Vec<Pokemon> = response.take(0)?;
+
+        Ok(pokemons)
+    }
+
+    async fn insert(&self, pokemon: Pokemon) -> Result<()> {
+        let id = Thing::from(("pokemon", pokemon.record_id().as_str()));
+        self.upsert(&id, pokemon).await
+    }
+
+    async fn move_details(&self) -> Result<Vec<MoveDetails>> {
+        let mut response = self
+            .db
+            .query("SELECT * FROM move_details WHERE version = $version ORDER BY name ASC")
+            .bind(("version", &self.version))
+            .await?;
+        let details: Vec<MoveDetails> = response.take(0)?;
+
+        Ok(details)
+    }
+
+    async fn insert_move_details(&self, details: MoveDetails) -> Result<()> {
+        let id = Thing::from(("move_details", format!("{}_{}", self.version, normalize(&details.name)).as_str()));
+        self.db
+            .query("UPDATE $id CONTENT $details")
+            .bind(("id", id))
+            .bind(("details", MoveDetailsRecord { version: self.version.clone(), details }))
+            .await?;
+        Ok(())
+    }
+
+    /// Only the moves with details can match, the filters are applied to the "move_details" table
+    async fn find_moves(&self, move_type: Option<PokemonType>, category: Option<MoveCategory>) -> Result<Vec<Move>> {
+        let mut conditions = vec!["version = $version"];
+        if move_type.is_some() {
+            conditions.push("move_type = $move_type");
+        }
+        if category.is_some() {
+            conditions.push("category = $category");
+        }
+        let mut response = self
+            .db
+            .query(format!("SELECT * FROM move_details WHERE {}", conditions.join(" AND ")))
+            .bind(("version", &self.version))
+            .bind(("move_type", move_type))
+            .bind(("category", category))
+            .await?;
+        let details: Vec<MoveDetails> = response.take(0)?;
+
+        let registry = MoveRegistry::new(&self.all().await?).with_details(details);
+        Ok(registry.moves.into_iter().filter(|m| m.details.is_some()).collect())
+    }
+
+    /// Finds the pokemon by species, the forms can be searched by any of their names: "Rapidash-G",
+    /// "Galarian Rapidash" or "Rapidash Galarian". When there is no exact match the species are
+    /// compared ignoring punctuation: "Mr Mime", "Farfetchd"
+    async fn find_pokemon(&self, species: &str) -> Result<Option<Pokemon>> {
+        let species = canonical_species(species);
+        if let Some(pokemon) = self.find_species(&species).await? {
+            return Ok(Some(pokemon));
+        }
+
+        let normalized = normalize(&species);
+        match self.species_names().await?.into_iter().find(|name| normalize(name) == normalized) {
+            Some(name) => self.find_species(&name).await,
+            None => Ok(None),
+        }
+    }
+
+    async fn species_names(&self) -> Result<Vec<String>> {
+        let mut response = self
+            .db
+            .query("SELECT species, dex_num FROM pokemon WHERE version = $version ORDER BY dex_num ASC")
+            .bind(("version", &self.version))
+            .await?;
+        let species: Vec<SpeciesRecord> = response.take(0)?;
+
+        Ok(species.into_iter().map(|s| s.species).collect())
+    }
+
+    /// The exact searches compare the normalized names, the query only narrows the partial ones and
+    /// every pokemon is checked with "learned_moves" like the memory store does
+    async fn find_by_move(&self, attack: &str, mode: MoveMatch) -> Result<Vec<Pokemon>> {
+        let condition = mode
+            .surql()
+            .map(|matches| {
+                format!(
+                    r#"AND (
+                array::len(lvl_up_moves[WHERE {matches}]) > 0
+                OR array::len(tms[WHERE {matches}]) > 0
+                OR array::len(egg_moves[WHERE {matches}]) > 0
+            )"#
+                )
+            })
+            .unwrap_or_default();
+        let query = format!(
             r#"
-        SELECT
-            dex_num, species, pokemon_type, new_pokemon_type,
-            stats, new_stats, abilities, lvl_up_moves, tms, egg_moves, locations
-        FROM pokemon
-        WHERE string::lowercase(species) = string::lowercase($species)
-        "#,
-        )
-        .bind(("species", species))
-        .await?;
-    let pokemon: Option<Pokemon> = response.take(0)?;
-    Ok(pokemon)
+            SELECT
+                version, dex_num, species, base_species, form, pokemon_type, new_pokemon_type,
+                stats, new_stats, abilities, held_items, lvl_up_moves, tms, egg_moves, locations
+            FROM pokemon
+            WHERE version = $version {condition}
+            ORDER BY dex_num ASC
+            "#
+        );
+        let mut response = self
+            .db
+            .query(query)
+            .bind(("version", &self.version))
+            .bind(("value", attack))
+            .await?;
+
+        let pokemons: Vec<Pokemon> = response.take(0)?;
+
+        Ok(pokemons.into_iter().filter(|p| !learned_moves(p, attack, mode).is_empty()).collect())
+    }
+
+    async fn find_by_tm(&self, tm: &str) -> Result<Vec<Pokemon>> {
+        let Some(num) = self.tm_number(tm).await? else {
+            return Ok(Vec::new());
+        };
+        let mut response = self
+            .db
+            .query(
+                r#"
+            SELECT
+                version, dex_num, species, base_species, form, pokemon_type, new_pokemon_type,
+                stats, new_stats, abilities, held_items, lvl_up_moves, tms, egg_moves, locations
+            FROM pokemon
+            WHERE version = $version AND array::len(tms[WHERE num = $num]) > 0
+            ORDER BY dex_num ASC
+            "#,
+            )
+            .bind(("version", &self.version))
+            .bind(("num", num))
+            .await?;
+        let pokemons: Vec<Pokemon> = response.take(0)?;
+
+        Ok(pokemons)
+    }
+
+    /// Finds the pokemon that gained the move in Lumi, moves marked with "(!!)" in the dex. The query
+    /// keeps the pokemon with a new move, the names are compared normalized afterwards
+    async fn find_by_new_move(&self, attack: &str) -> Result<Vec<Pokemon>> {
+        let mut response = self
+            .db
+            .query(
+                r#"
+            SELECT
+                version, dex_num, species, base_species, form, pokemon_type, new_pokemon_type,
+                stats, new_stats, abilities, held_items, lvl_up_moves, tms, egg_moves, locations
+            FROM pokemon
+            WHERE version = $version AND (
+                array::len(lvl_up_moves[WHERE is_new = true]) > 0
+                OR array::len(tms[WHERE is_new = true]) > 0
+                OR array::len(egg_moves[WHERE is_new = true]) > 0
+            )
+            ORDER BY dex_num ASC
+            "#,
+            )
+            .bind(("version", &self.version))
+            .await?;
+
+        let pokemons: Vec<Pokemon> = response.take(0)?;
+
+        Ok(pokemons
+            .into_iter()
+            .filter(|p| learned_moves(p, attack, MoveMatch::Exact).iter().any(|m| m.is_new))
+            .collect())
+    }
+
+    async fn find_by_held_item(&self, item: &str) -> Result<Vec<Pokemon>> {
+        let mut response = self
+            .db
+            .query(
+                r#"
+            SELECT
+                version, dex_num, species, base_species, form, pokemon_type, new_pokemon_type,
+                stats, new_stats, abilities, held_items, lvl_up_moves, tms, egg_moves, locations
+            FROM pokemon
+            WHERE version = $version
+            AND array::len(held_items[WHERE string::lowercase(name) = string::lowercase($item)]) > 0
+            ORDER BY dex_num ASC
+            "#,
+            )
+            .bind(("version", &self.version))
+            .bind(("item", item))
+            .await?;
+
+        let pokemons: Vec<Pokemon> = response.take(0)?;
+
+        Ok(pokemons)
+    }
+
+    async fn search(&self, query: &PokemonQuery) -> Result<Vec<Pokemon>> {
+        let (condition, bindings) = query.surql_condition();
+        let mut request = self
+            .db
+            .query(format!(
+                r#"
+            SELECT
+                version, dex_num, species, base_species, form, pokemon_type, new_pokemon_type,
+                stats, new_stats, abilities, held_items, lvl_up_moves, tms, egg_moves, locations
+            FROM pokemon
+            WHERE version = $version AND {}
+            "#,
+                condition
+            ))
+            .bind(("version", &self.version));
+        for binding in bindings {
+            request = request.bind(binding);
+        }
+        let mut response = request.await?;
+        let mut pokemons: Vec<Pokemon> = response.take(0)?;
+        query.sort(&mut pokemons);
+
+        Ok(pokemons)
+    }
+
+    /// Fetches the whole evolution family of the species from the "evolves_to" relations
+    async fn find_evolution_chain(&self, species: &str) -> Result<EvolutionChain> {
+        let mut response = self
+            .db
+            .query(
+                r#"
+            SELECT in.species AS from, out.species AS to, method, condition
+            FROM evolves_to
+            WHERE in.version = $version
+            "#,
+            )
+            .bind(("version", &self.version))
+            .await?;
+        let evolutions: Vec<Evolution> = response.take(0)?;
+
+        Ok(evolution_chain(&evolutions, species))
+    }
 }
 
-pub async fn find_by_move(attack: &str) -> Result<Vec<Pokemon>> {
-    let mut response = DB
-        .query(
-            r#"
-        SELECT
-            dex_num, species, pokemon_type, new_pokemon_type,
-            stats, new_stats, abilities, lvl_up_moves, tms, egg_moves, locations
-        FROM pokemon
-        WHERE string::lowercase(lvl_up_moves.*.name) CONTAINS string::lowercase($value)
-		OR string::lowercase(tms.*.name) CONTAINS string::lowercase($value)
-		OR string::lowercase(egg_moves) CONTAINS string::lowercase($value)
-		ORDER BY dex_num ASC
-        "#,
-        )
-        .bind(("value", attack))
-        .await?;
-
-    let pokemons: Vec<Pokemon> = response.take(0)?;
-
-    Ok(pokemons)
+#[derive(Debug, Deserialize)]
+struct SpeciesRecord {
+    species: String,
 }
 
-pub async fn get_image(pokemon: &str) -> Result<RetainedImage> {
-    let url = format!("https://img.pokemondb.net/artwork/large/{}.jpg", pokemon.to_lowercase());
-    let bytes = reqwest::get(&url).await?.bytes().await?;
-    let image = RetainedImage::from_image_bytes(&url, &bytes).map_err(anyhow::Error::msg);
-    image
+#[derive(Debug, Deserialize)]
+struct VersionRecord {
+    version: String,
 }
 
-fn load_pokedex() -> Result<Vec<Pokemon>> {
-    let files = [
-        "pokedex/Lumi Pokédex 001-151 Kanto Pokémon.txt",
-        "pokedex/Lumi Pokédex 152-251 Johto Pokémon.txt",
-        "pokedex/Lumi Pokédex 252-386 Hoenn Pokémon.txt",
-        "pokedex/Lumi Pokédex 387-493 Sinnoh Pokémon.txt",
-        "pokedex/Lumi Pokédex xxx 2.0 Add-Ons + Form Pokémon.txt"
-    ];
+#[derive(Debug, Serialize)]
+struct MoveDetailsRecord {
+    version: String,
+    #[serde(flatten)]
+    details: MoveDetails,
+}
 
-    let mut pokedex = Vec::new();
+#[derive(Debug, Deserialize)]
+struct StoredPokemon {
+    id: Thing,
+    #[serde(flatten)]
+    pokemon: Pokemon,
+}
 
-    for file in files {
-        let mut path = env::current_dir()?;
-        path.push(file);
-        let pokedex_string = fs::read_to_string(path)?;
-        let (_, mut parsed_pokedex) = parse_pokedex(&pokedex_string)?;
-        pokedex.append(&mut parsed_pokedex);
-    }
+/// Species added, changed and removed by an import
+#[derive(Debug, Default)]
+pub struct ImportSummary {
+    pub added: Vec<String>,
+    pub changed: Vec<String>,
+    pub removed: Vec<String>,
+    /// Number of moves with details
+    pub move_details: usize,
+    /// Evolutions between species that aren't both in the dex
+    pub unrelated: Vec<String>,
+}
 
-    Ok(pokedex)
+impl fmt::Display for ImportSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Added {}, changed {}, removed {} pokemon",
+            self.added.len(),
+            self.changed.len(),
+            self.removed.len()
+        )?;
+        if self.move_details > 0 {
+            write!(f, ", imported the details of {} moves", self.move_details)?;
+        }
+        for species in &self.added {
+            write!(f, "\n+ {}", species)?;
+        }
+        for species in &self.changed {
+            write!(f, "\n~ {}", species)?;
+        }
+        for species in &self.removed {
+            write!(f, "\n- {}", species)?;
+        }
+        if !self.unrelated.is_empty() {
+            write!(f, "\nCould not relate {} evolutions:", self.unrelated.len())?;
+            for evolution in &self.unrelated {
+                write!(f, "\n  {}", evolution)?;
+            }
+        }
+        Ok(())
+    }
 }
 
-pub async fn store_pokedex() -> Result<()> {
-    let pokedex = load_pokedex()?;
-
-    for mut pokemon in pokedex {
-        match find_pokemon(&pokemon.species).await? {
-            Some(found) => {println!("{}", found.species);},
-            None => {
-				pokemon.species = pokemon.species
-                .replace("Cloak","")
-                .replace("Form","")
-                .replace("-A"," Alolan")
-                .replace("-G"," Galarian")
-                .replace("-H"," Hisuian")
-                .trim().to_string();
-				let _created: Pokemon = DB.create("pokemon").content(pokemon).await?;
-			},
-        }
-    }
-    Ok(())
+pub async fn get_image(pokemon: &str) -> Result<RetainedImage> {
+    let url = format!("https://img.pokemondb.net/artwork/large/{}.jpg", pokemon.to_lowercase());
+    let bytes = reqwest::get(&url).await?.bytes().await?;
+    let image = RetainedImage::from_image_bytes(&url, &bytes).map_err(anyhow::Error::msg);
+    image
 }
@@ -0,0 +1,44 @@
+/// This library holds the locations used by the program: where the database is stored and where the
+/// pokedex text files are read from
+use std::{env, path::PathBuf};
+
+/// Lumi release of the pokedex files shipped with the program
+pub const DEFAULT_VERSION: &str = "2.0F";
+
+/// Where the database is kept, the in memory database is lost when the program exits
+#[derive(Debug, Clone)]
+pub enum Storage {
+    File(PathBuf),
+    Memory,
+}
+
+/// Per user directory for the program data, follows the convention of each OS:
+/// "~/.local/share/lumi_dex", "~/Library/Application Support/lumi_dex" or "%APPDATA%\lumi_dex"
+pub fn user_data_dir() -> PathBuf {
+    let base = if cfg!(windows) {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        env::var_os("XDG_DATA_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+    };
+
+    base.unwrap_or_else(|| PathBuf::from(".")).join("lumi_dex")
+}
+
+pub fn default_db_path() -> PathBuf {
+    user_data_dir().join("lumi_dex.db")
+}
+
+/// The "pokedex" folder of the working directory when there is one (running from the repository),
+/// the "pokedex" folder of the user data directory otherwise
+pub fn default_data_dir() -> PathBuf {
+    let local = PathBuf::from("pokedex");
+    match local.is_dir() {
+        true => local,
+        false => user_data_dir().join("pokedex"),
+    }
+}
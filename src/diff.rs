@@ -0,0 +1,241 @@
+/// This library compares two releases of the pokedex and lists what changed for every pokemon: stats,
+/// typing, abilities, moves and locations. The changes use the in game data (the Lumi stats and typing)
+use serde::Serialize;
+use std::{fmt, path::Path};
+use anyhow::Result;
+
+use crate::{load_pokedex, ParseError, Pokemon};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffStatus {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MoveList {
+    LevelUp,
+    TM,
+    Egg,
+}
+
+impl fmt::Display for MoveList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveList::LevelUp => write!(f, "Level up"),
+            MoveList::TM => write!(f, "TM"),
+            MoveList::Egg => write!(f, "Egg move"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum Change {
+    Stat { stat: String, old: Option<u16>, new: Option<u16> },
+    Typing { old: String, new: String },
+    AbilityAdded { ability: String },
+    AbilityRemoved { ability: String },
+    MoveAdded { list: MoveList, entry: String },
+    MoveRemoved { list: MoveList, entry: String },
+    LocationAdded { location: String },
+    LocationRemoved { location: String },
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stat = |value: &Option<u16>| value.map_or(String::from("-"), |v| v.to_string());
+        match self {
+            Change::Stat { stat: name, old, new } => write!(f, "{}: {} => {}", name, stat(old), stat(new)),
+            Change::Typing { old, new } => write!(f, "Type: {} => {}", old, new),
+            Change::AbilityAdded { ability } => write!(f, "+ Ability: {}", ability),
+            Change::AbilityRemoved { ability } => write!(f, "- Ability: {}", ability),
+            Change::MoveAdded { list, entry } => write!(f, "+ {}: {}", list, entry),
+            Change::MoveRemoved { list, entry } => write!(f, "- {}: {}", list, entry),
+            Change::LocationAdded { location } => write!(f, "+ Location: {}", location),
+            Change::LocationRemoved { location } => write!(f, "- Location: {}", location),
+        }
+    }
+}
+
+/// The changes of a single pokemon, added and removed pokemon don't list changes
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PokemonDiff {
+    pub dex_num: u32,
+    pub species: String,
+    pub status: DiffStatus,
+    pub changes: Vec<Change>,
+}
+
+impl fmt::Display for PokemonDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.status {
+            DiffStatus::Added => write!(f, "{:03} {} (added)", self.dex_num, self.species),
+            DiffStatus::Removed => write!(f, "{:03} {} (removed)", self.dex_num, self.species),
+            DiffStatus::Changed => {
+                write!(f, "{:03} {}", self.dex_num, self.species)?;
+                for change in &self.changes {
+                    write!(f, "\n\t{}", change)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Parses the pokedex files of both directories and compares them. The entries that could not be
+/// parsed are returned too, they show up in the changes as added or removed pokemon
+pub fn diff_dirs(old_dir: &Path, new_dir: &Path) -> Result<(Vec<PokemonDiff>, Vec<ParseError>)> {
+    let (old, mut skipped) = load_pokedex(old_dir)?;
+    let (new, new_skipped) = load_pokedex(new_dir)?;
+    skipped.extend(new_skipped);
+    Ok((diff_pokedex(&old, &new), skipped))
+}
+
+/// Compares the pokemon by species, the result is sorted by dex number and only has the pokemon that
+/// changed
+pub fn diff_pokedex(old: &[Pokemon], new: &[Pokemon]) -> Vec<PokemonDiff> {
+    let find = |pokedex: &[Pokemon], species: &str| {
+        let species = species.to_lowercase();
+        pokedex.iter().find(|p| p.species.to_lowercase() == species).cloned()
+    };
+
+    let mut diffs = Vec::new();
+    for pokemon in new {
+        let (status, changes) = match find(old, &pokemon.species) {
+            Some(old_pokemon) => (DiffStatus::Changed, changes(&old_pokemon, pokemon)),
+            None => (DiffStatus::Added, Vec::new()),
+        };
+        if status == DiffStatus::Changed && changes.is_empty() {
+            continue;
+        }
+        diffs.push(PokemonDiff {
+            dex_num: pokemon.dex_num,
+            species: pokemon.species.clone(),
+            status,
+            changes,
+        });
+    }
+    for pokemon in old.iter().filter(|p| find(new, &p.species).is_none()) {
+        diffs.push(PokemonDiff {
+            dex_num: pokemon.dex_num,
+            species: pokemon.species.clone(),
+            status: DiffStatus::Removed,
+            changes: Vec::new(),
+        });
+    }
+
+    diffs.sort_by_key(|d| d.dex_num);
+    diffs
+}
+
+/// The entries of a move list as text: "16 Vine Whip", "TM06 Toxic"
+type MoveEntries = fn(&Pokemon) -> Vec<String>;
+
+/// Every difference between two releases of the same pokemon
+pub fn changes(old: &Pokemon, new: &Pokemon) -> Vec<Change> {
+    let mut changes = Vec::new();
+
+    let old_stats: Vec<(&str, Option<u16>)> = old.get_stats().into();
+    let new_stats: Vec<(&str, Option<u16>)> = new.get_stats().into();
+    for ((stat, old_value), (_, new_value)) in old_stats.into_iter().zip(new_stats) {
+        if old_value != new_value {
+            changes.push(Change::Stat { stat: stat.to_string(), old: old_value, new: new_value });
+        }
+    }
+
+    if old.get_typing() != new.get_typing() {
+        changes.push(Change::Typing {
+            old: old.get_typing().to_string(),
+            new: new.get_typing().to_string(),
+        });
+    }
+
+    let (added, removed) = added_removed(&old.abilities.names(), &new.abilities.names());
+    changes.extend(removed.into_iter().map(|ability| Change::AbilityRemoved { ability }));
+    changes.extend(added.into_iter().map(|ability| Change::AbilityAdded { ability }));
+
+    let moves: [(MoveList, MoveEntries); 3] = [
+        (MoveList::LevelUp, |p| p.lvl_up_moves.iter().map(|m| format!("{} {}", m.lvl, m.name)).collect()),
+        (MoveList::TM, |p| p.tms.iter().map(|m| format!("TM{:02} {}", m.num, m.name)).collect()),
+        (MoveList::Egg, |p| p.egg_moves.iter().map(|m| m.name.clone()).collect()),
+    ];
+    for (list, entries) in moves {
+        let (added, removed) = added_removed(&entries(old), &entries(new));
+        changes.extend(removed.into_iter().map(|entry| Change::MoveRemoved { list, entry }));
+        changes.extend(added.into_iter().map(|entry| Change::MoveAdded { list, entry }));
+    }
+
+    let locations = |p: &Pokemon| p.locations.iter().map(|l| l.to_string()).collect::<Vec<String>>();
+    let (added, removed) = added_removed(&locations(old), &locations(new));
+    changes.extend(removed.into_iter().map(|location| Change::LocationRemoved { location }));
+    changes.extend(added.into_iter().map(|location| Change::LocationAdded { location }));
+
+    changes
+}
+
+/// The entries only in "new" and the entries only in "old"
+fn added_removed(old: &[String], new: &[String]) -> (Vec<String>, Vec<String>) {
+    let added = new.iter().filter(|x| !old.contains(x)).cloned().collect();
+    let removed = old.iter().filter(|x| !new.contains(x)).cloned().collect();
+    (added, removed)
+}
+
+/// The changelog as a plain text list
+pub fn diff_text(diffs: &[PokemonDiff]) -> String {
+    diffs.iter().map(|d| d.to_string()).collect::<Vec<String>>().join("\n\n")
+}
+
+/// The changelog with a section for every pokemon
+pub fn diff_markdown(diffs: &[PokemonDiff]) -> String {
+    let mut markdown = Vec::new();
+    for diff in diffs {
+        let status = match diff.status {
+            DiffStatus::Added => " (added)",
+            DiffStatus::Removed => " (removed)",
+            DiffStatus::Changed => "",
+        };
+        markdown.push(format!("## {:03} {}{}\n", diff.dex_num, diff.species, status));
+        for change in &diff.changes {
+            markdown.push(format!("- {}", change));
+        }
+        markdown.push(String::new());
+    }
+    markdown.join("\n")
+}
+
+pub fn diff_json(diffs: &[PokemonDiff]) -> Result<String> {
+    Ok(serde_json::to_string_pretty(diffs)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_pokedex, parser::test_fixtures::*};
+
+    #[test]
+    fn changelog_between_releases() {
+        let (_, old) = parse_pokedex(BULBASAUR).unwrap();
+        let mut new = old.clone();
+        new[0].stats.hp = Some(50);
+        new[0].abilities.hidden = Some(String::from("Thick Fat"));
+        new[0].egg_moves.clear();
+
+        let diffs = diff_pokedex(&old, &new);
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(
+            diffs[0].changes,
+            [
+                Change::Stat { stat: String::from("HP"), old: Some(45), new: Some(50) },
+                Change::AbilityRemoved { ability: String::from("Grassy Surge") },
+                Change::AbilityAdded { ability: String::from("Thick Fat") },
+                Change::MoveRemoved { list: MoveList::Egg, entry: String::from("Skull Bash") },
+            ]
+        );
+        assert!(diff_pokedex(&old, &old).is_empty());
+        assert_eq!(diff_pokedex(&old, &[])[0].status, DiffStatus::Removed);
+    }
+}
@@ -0,0 +1,227 @@
+/// This library builds the evolution graph of the pokedex. The dex doesn't have an evolution field,
+/// the evolutions are listed as locations of the evolved pokemon: "Evolve Ivysaur (Level 32)"
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::pokemon::*;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum EvolutionMethod {
+    Level(u8),
+    Item(String),
+    /// Level up holding the item: "Level up holding Oval Stone during daytime"
+    HeldItem(String),
+    /// Level up under a condition that isn't a level: "Level Up with Mimic learnt" => "with Mimic learnt"
+    LevelUp(String),
+    Friendship,
+    Trade,
+    Other,
+}
+
+impl fmt::Display for EvolutionMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvolutionMethod::Level(lvl) => write!(f, "Level {}", lvl),
+            EvolutionMethod::Item(item) => write!(f, "Use {}", item),
+            EvolutionMethod::HeldItem(item) => write!(f, "Level up holding {}", item),
+            EvolutionMethod::LevelUp(condition) => write!(f, "Level up {}", condition),
+            EvolutionMethod::Friendship => write!(f, "Friendship"),
+            EvolutionMethod::Trade => write!(f, "Trade"),
+            EvolutionMethod::Other => write!(f, "Other"),
+        }
+    }
+}
+
+/// An edge of the evolution graph, "condition" holds the full text of the dex
+/// ("Level 20 at Nighttime") while "method" is the main requirement
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Evolution {
+    pub from: String,
+    pub to: String,
+    pub method: EvolutionMethod,
+    pub condition: String,
+}
+
+impl fmt::Display for Evolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} => {} ({})", self.from, self.to, self.condition)
+    }
+}
+
+/// Every stage of an evolution family, starting from the base species. A stage can have more than
+/// one species when the family branches: [[Eevee], [Vaporeon, Jolteon, ...]]
+#[derive(Debug, Clone, Default)]
+pub struct EvolutionChain {
+    pub stages: Vec<Vec<String>>,
+    pub evolutions: Vec<Evolution>,
+}
+
+impl EvolutionChain {
+    /// The evolution that leads to the species, None for the base species
+    pub fn evolution_to(&self, species: &str) -> Option<&Evolution> {
+        self.evolutions.iter().find(|e| e.to == species)
+    }
+}
+
+/// Collects the evolutions of the pokedex from the "Evolve X (condition)" locations
+pub fn evolutions(pokedex: &[Pokemon]) -> Vec<Evolution> {
+    pokedex
+        .iter()
+        .flat_map(|pokemon| {
+            pokemon
+                .locations
+                .iter()
+                .filter(|location| location.method == EncounterMethod::Evolution)
+                .map(|location| Evolution {
+                    from: location.area.clone(),
+                    to: pokemon.species.clone(),
+                    method: evolution_method(&location.qualifiers),
+                    condition: location.qualifiers.join(", "),
+                })
+        })
+        .collect()
+}
+
+/// Finds the main requirement of the evolution in the condition: "Male, Level 20" => Level(20)
+pub fn evolution_method(condition: &[String]) -> EvolutionMethod {
+    for part in condition {
+        let part = part.trim();
+        if let Some(level) = part.strip_prefix("Level ") {
+            let level: String = level.chars().take_while(|c| c.is_ascii_digit()).collect();
+            if let Ok(level) = level.parse() {
+                return EvolutionMethod::Level(level);
+            }
+        }
+        // "Level up" is spelled with both cases in the dex
+        if part.get(..9).is_some_and(|start| start.eq_ignore_ascii_case("Level up ")) {
+            let condition = part[9..].trim();
+            return match held_item(condition) {
+                Some(item) => EvolutionMethod::HeldItem(item),
+                None => EvolutionMethod::LevelUp(condition.to_string()),
+            };
+        }
+        if part.starts_with("Happiness") {
+            return EvolutionMethod::Friendship;
+        }
+        if let Some(item) = part.strip_prefix("Use ").or_else(|| part.strip_prefix("use ")) {
+            return EvolutionMethod::Item(item.trim().to_string());
+        }
+        if part.ends_with("Stone") {
+            return EvolutionMethod::Item(part.to_string());
+        }
+        if part.contains("Trade") {
+            return EvolutionMethod::Trade;
+        }
+    }
+    EvolutionMethod::Other
+}
+
+/// The item of a level up condition: "while holding Razor Fang during Nighttime" or "with Razor Claw
+/// in daytime" => "Razor Claw". The other "with" conditions are moves learnt, party members or stats
+fn held_item(condition: &str) -> Option<String> {
+    let item = match condition.strip_prefix("while holding ").or_else(|| condition.strip_prefix("holding ")) {
+        Some(item) => item,
+        None => condition
+            .strip_prefix("with ")
+            .filter(|item| !["learnt", "learned", "Party", "stat"].iter().any(|word| item.contains(word)))?,
+    };
+    let end = [" during ", " in ", " at "].iter().filter_map(|time| item.find(time)).min().unwrap_or(item.len());
+    Some(item[..end].trim().to_string())
+}
+
+/// Builds the whole family of the species, the species names are compared ignoring case
+pub fn evolution_chain(evolutions: &[Evolution], species: &str) -> EvolutionChain {
+    let same = |a: &str, b: &str| a.to_lowercase() == b.to_lowercase();
+
+    // walk back to the base species, the visited list guards against cycles in bad data
+    let mut base = species.to_string();
+    let mut visited = vec![base.to_lowercase()];
+    while let Some(evolution) = evolutions.iter().find(|e| same(&e.to, &base)) {
+        if visited.contains(&evolution.from.to_lowercase()) {
+            break;
+        }
+        base = evolution.from.clone();
+        visited.push(base.to_lowercase());
+    }
+
+    let mut chain = EvolutionChain::default();
+    let mut stage = vec![base];
+    let mut seen: Vec<String> = Vec::new();
+    while !stage.is_empty() {
+        seen.extend(stage.iter().map(|s| s.to_lowercase()));
+        let mut next = Vec::new();
+        for evolution in evolutions {
+            if stage.iter().any(|s| same(s, &evolution.from)) && !seen.contains(&evolution.to.to_lowercase()) {
+                if !next.contains(&evolution.to) {
+                    next.push(evolution.to.clone());
+                }
+                chain.evolutions.push(evolution.clone());
+            }
+        }
+        chain.stages.push(stage);
+        stage = next;
+    }
+
+    chain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evolution(from: &str, to: &str, condition: &str) -> Evolution {
+        let condition = vec![condition.to_string()];
+        Evolution {
+            from: from.to_string(),
+            to: to.to_string(),
+            method: evolution_method(&condition),
+            condition: condition.join(", "),
+        }
+    }
+
+    #[test]
+    fn chain_from_any_stage() {
+        let evolutions = [
+            evolution("Eevee", "Vaporeon", "Use Water Stone"),
+            evolution("Eevee", "Espeon", "Happiness"),
+            evolution("Charmander", "Charmeleon", "Level 16"),
+            evolution("Charmeleon", "Charizard", "Level 36"),
+        ];
+
+        let chain = evolution_chain(&evolutions, "charizard");
+        assert_eq!(chain.stages, [["Charmander"], ["Charmeleon"], ["Charizard"]]);
+        assert_eq!(chain.evolution_to("Charizard").unwrap().method, EvolutionMethod::Level(36));
+
+        let chain = evolution_chain(&evolutions, "Espeon");
+        assert_eq!(chain.stages, vec![vec!["Eevee"], vec!["Vaporeon", "Espeon"]]);
+        assert_eq!(chain.evolution_to("Espeon").unwrap().method, EvolutionMethod::Friendship);
+        assert_eq!(
+            chain.evolution_to("Vaporeon").unwrap().method,
+            EvolutionMethod::Item("Water Stone".to_string())
+        );
+
+        let method = |condition: &str| evolution("", "", condition).method;
+        assert_eq!(method("Level up holding Oval Stone during daytime"), EvolutionMethod::HeldItem("Oval Stone".to_string()));
+        assert_eq!(method("Level up with Razor Claw in daytime"), EvolutionMethod::HeldItem("Razor Claw".to_string()));
+        assert_eq!(method("Level Up with Mimic learnt"), EvolutionMethod::LevelUp("with Mimic learnt".to_string()));
+        assert_eq!(method("Level Up with Remoraid in Party").to_string(), "Level up with Remoraid in Party");
+    }
+
+    /// Every evolution of the dex files has a condition and a known method
+    #[test]
+    fn evolutions_of_the_dex_files() {
+        let evolutions = evolutions(&crate::parser::test_fixtures::dex_files());
+        assert!(evolutions.len() > 200);
+        let missing: Vec<String> = evolutions
+            .iter()
+            .filter(|e| e.condition.is_empty() || e.method == EvolutionMethod::Other)
+            .map(|e| e.to_string())
+            .collect();
+        assert!(missing.is_empty(), "{}", missing.join("\n"));
+
+        let lucario = evolutions.iter().find(|e| e.to == "Lucario").unwrap();
+        assert_eq!(lucario.method, EvolutionMethod::LevelUp("with Aura Sphere learned".to_string()));
+        let weavile = evolutions.iter().find(|e| e.to == "Weavile").unwrap();
+        assert_eq!(weavile.method, EvolutionMethod::HeldItem("Razor Claw".to_string()));
+    }
+}
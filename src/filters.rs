@@ -0,0 +1,130 @@
+use egui::{ComboBox, DragValue, Grid};
+
+use crate::query::{PokemonQuery, QuerySort, Stat};
+use crate::type_chart::PokemonType;
+
+/// State of the filter sidebar, a stat or dex bound of 0 is not used
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QueryFilter {
+    pub types: [Option<PokemonType>; 2],
+    pub ability: String,
+    pub hidden_ability: bool,
+    pub min_stats: [u16; 7],
+    pub max_stats: [u16; 7],
+    pub min_dex: u32,
+    pub max_dex: u32,
+    pub sort: QuerySort,
+    pub descending: bool,
+}
+
+impl QueryFilter {
+    pub fn query(&self) -> PokemonQuery {
+        let mut query = PokemonQuery::new().sort_by(self.sort, self.descending);
+        for pokemon_type in self.types.iter().flatten() {
+            query = query.with_type(*pokemon_type);
+        }
+        if !self.ability.trim().is_empty() {
+            query = query.ability(self.ability.trim());
+            if self.hidden_ability {
+                query = query.hidden_ability_only();
+            }
+        }
+        for (i, stat) in Stat::ALL.iter().enumerate() {
+            if self.min_stats[i] > 0 {
+                query = query.min(*stat, self.min_stats[i]);
+            }
+            if self.max_stats[i] > 0 {
+                query = query.max(*stat, self.max_stats[i]);
+            }
+        }
+        if self.min_dex > 0 {
+            query.min_dex = Some(self.min_dex);
+        }
+        if self.max_dex > 0 {
+            query.max_dex = Some(self.max_dex);
+        }
+        query
+    }
+}
+
+// A wrapper that allows the more idiomatic usage pattern: `ui.add(query_filter(&mut filter))`
+pub fn query_filter(filter: &mut QueryFilter) -> impl egui::Widget + '_ {
+    move |ui: &mut egui::Ui| query_filter_ui(ui, filter)
+}
+
+/// The response is marked as changed when a filter changes
+pub fn query_filter_ui(ui: &mut egui::Ui, filter: &mut QueryFilter) -> egui::Response {
+    let before = filter.clone();
+
+    let mut response = ui
+        .vertical(|ui| {
+            ui.strong("Type");
+            ui.horizontal(|ui| {
+                for (i, selected) in filter.types.iter_mut().enumerate() {
+                    ComboBox::from_id_source(ui.make_persistent_id(("type", i)))
+                        .selected_text(selected.map_or(String::from("Any"), |t| t.to_string()))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(selected, None, "Any");
+                            for pokemon_type in PokemonType::ALL {
+                                ui.selectable_value(selected, Some(pokemon_type), pokemon_type.to_string());
+                            }
+                        });
+                }
+            });
+
+            ui.strong("Ability");
+            ui.text_edit_singleline(&mut filter.ability);
+            ui.checkbox(&mut filter.hidden_ability, "Hidden ability only");
+
+            ui.strong("Stats");
+            Grid::new(ui.make_persistent_id("stats")).num_columns(3).show(ui, |ui| {
+                ui.label("");
+                ui.label("Min");
+                ui.label("Max");
+                ui.end_row();
+                for (i, stat) in Stat::ALL.iter().enumerate() {
+                    let limit = match stat {
+                        Stat::Bst => 1530,
+                        _ => 255,
+                    };
+                    ui.label(stat.to_string());
+                    ui.add(DragValue::new(&mut filter.min_stats[i]).clamp_range(0..=limit));
+                    ui.add(DragValue::new(&mut filter.max_stats[i]).clamp_range(0..=limit));
+                    ui.end_row();
+                }
+            });
+
+            ui.strong("Dex number");
+            ui.horizontal(|ui| {
+                ui.add(DragValue::new(&mut filter.min_dex).clamp_range(0..=999));
+                ui.label("to");
+                ui.add(DragValue::new(&mut filter.max_dex).clamp_range(0..=999));
+            });
+
+            ui.strong("Sort by");
+            ui.horizontal(|ui| {
+                let sort_name = |sort: &QuerySort| match sort {
+                    QuerySort::Dex => String::from("Dex"),
+                    QuerySort::Species => String::from("Species"),
+                    QuerySort::Stat(stat) => stat.to_string(),
+                };
+                ComboBox::from_id_source(ui.make_persistent_id("sort"))
+                    .selected_text(sort_name(&filter.sort))
+                    .show_ui(ui, |ui| {
+                        let sorts = [QuerySort::Dex, QuerySort::Species]
+                            .into_iter()
+                            .chain(Stat::ALL.map(QuerySort::Stat));
+                        for sort in sorts {
+                            ui.selectable_value(&mut filter.sort, sort, sort_name(&sort));
+                        }
+                    });
+                ui.checkbox(&mut filter.descending, "Descending");
+            });
+        })
+        .response;
+
+    if *filter != before {
+        response.mark_changed();
+    }
+    response
+}
@@ -0,0 +1,96 @@
+/// This library holds the fuzzy matching used when a species is not found: the names are normalized
+/// ("Farfetch'd" => "farfetchd", "Nidoran♀" => "nidoranf") and ranked by edit distance
+use std::cmp::min;
+
+/// Lowercase name without punctuation or spaces, the gender symbols become "f" and "m" so "Nidoran F"
+/// and "Nidoran♀" are the same name
+pub fn normalize(name: &str) -> String {
+    name.chars()
+        .flat_map(|c| match c {
+            '♀' => vec!['f'],
+            '♂' => vec!['m'],
+            c if c.is_alphanumeric() => c.to_lowercase().collect(),
+            _ => vec![],
+        })
+        .collect()
+}
+
+/// Levenshtein distance between both strings, counted in chars
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(min(min(previous[j + 1] + 1, current[j] + 1), substitution));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// How close a candidate is to the search, lower is better
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Rank {
+    Same,
+    Prefix,
+    Contains,
+    Typo,
+}
+
+/// The candidates closest to the search, best first. Only names that start with the search, contain it
+/// or are a few typos away are returned
+pub fn closest_matches(search: &str, candidates: &[String], limit: usize) -> Vec<String> {
+    let search = normalize(search);
+    if search.is_empty() {
+        return Vec::new();
+    }
+    // one typo every three letters
+    let max_distance = (search.chars().count() / 3).max(1);
+
+    let mut ranked: Vec<(Rank, usize, &String)> = candidates
+        .iter()
+        .filter_map(|candidate| {
+            let name = normalize(candidate);
+            let distance = edit_distance(&search, &name);
+            let rank = if name == search {
+                Rank::Same
+            } else if name.starts_with(&search) {
+                Rank::Prefix
+            } else if name.contains(&search) {
+                Rank::Contains
+            } else if distance <= max_distance {
+                Rank::Typo
+            } else {
+                return None;
+            };
+            Some((rank, distance, candidate))
+        })
+        .collect();
+    ranked.sort();
+    ranked.dedup_by(|a, b| a.2 == b.2);
+
+    ranked.into_iter().take(limit).map(|(_, _, candidate)| candidate.clone()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranked_suggestions() {
+        let species: Vec<String> = ["Farfetch'd", "Nidoran♀", "Nidoran♂", "Mr. Mime", "Mime Jr.", "Pikachu", "Raichu"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        assert_eq!(normalize("Nidoran F"), normalize("Nidoran♀"));
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(closest_matches("Farfetchd", &species, 3), ["Farfetch'd"]);
+        assert_eq!(closest_matches("mr mime", &species, 3), ["Mr. Mime"]);
+        assert_eq!(closest_matches("nidoran", &species, 3), ["Nidoran♀", "Nidoran♂"]);
+        assert_eq!(closest_matches("Pikachoo", &species, 3), ["Pikachu"]);
+        assert!(closest_matches("Charizard", &species, 3).is_empty());
+    }
+}
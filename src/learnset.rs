@@ -0,0 +1,215 @@
+/// This library tells how a pokemon learns a move: by level up, by TM or as an egg move. The move
+/// searches match the whole name by default, "Punch" only finds the moves named "Punch"
+use serde::Serialize;
+use std::fmt;
+
+use crate::{normalize, MoveList, Pokemon};
+
+/// How the searched move name is compared to the names of the dex, both ignore the case
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MoveMatch {
+    /// The whole name: "Surf" doesn't find "Surging Strikes". The names are compared normalized since
+    /// the dex spells the same move several ways: "Extremespeed" finds "Extreme Speed"
+    #[default]
+    Exact,
+    /// Any move containing the search: "Punch" finds "Fire Punch" and "Drain Punch"
+    Partial,
+}
+
+impl MoveMatch {
+    /// The search as "matches" compares it to the names
+    pub fn key(&self, search: &str) -> String {
+        match self {
+            MoveMatch::Exact => normalize(search),
+            MoveMatch::Partial => search.trim().to_lowercase(),
+        }
+    }
+
+    /// "search" must be the "key" of the search
+    pub fn matches(&self, name: &str, search: &str) -> bool {
+        match self {
+            MoveMatch::Exact => normalize(name) == search,
+            MoveMatch::Partial => name.to_lowercase().contains(search),
+        }
+    }
+
+    /// SurrealQL comparison of the "name" field of a move with "$value". The normalized names can't be
+    /// computed in SurrealQL, the exact matches are filtered after the query
+    pub fn surql(&self) -> Option<&'static str> {
+        match self {
+            MoveMatch::Exact => None,
+            MoveMatch::Partial => Some("string::lowercase(name) CONTAINS string::lowercase($value)"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(tag = "method", content = "value", rename_all = "snake_case")]
+pub enum MoveLearn {
+    Level(u8),
+    TM(u16),
+    Egg,
+}
+
+impl MoveLearn {
+    pub fn list(&self) -> MoveList {
+        match self {
+            MoveLearn::Level(_) => MoveList::LevelUp,
+            MoveLearn::TM(_) => MoveList::TM,
+            MoveLearn::Egg => MoveList::Egg,
+        }
+    }
+
+    /// The level or the TM, egg moves don't have one
+    pub fn detail(&self) -> String {
+        match self {
+            MoveLearn::Level(lvl) => lvl.to_string(),
+            MoveLearn::TM(num) => format!("TM{:02}", num),
+            MoveLearn::Egg => String::new(),
+        }
+    }
+}
+
+impl fmt::Display for MoveLearn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveLearn::Level(lvl) => write!(f, "Level {}", lvl),
+            MoveLearn::TM(num) => write!(f, "TM{:02}", num),
+            MoveLearn::Egg => write!(f, "Egg move"),
+        }
+    }
+}
+
+/// A move of the pokemon and how it's learnt, "is_new" is set when it was gained in Lumi
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LearnedMove {
+    pub name: String,
+    pub method: MoveLearn,
+    pub is_new: bool,
+}
+
+impl fmt::Display for LearnedMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}{}", self.method, self.name, if self.is_new { " (!!)" } else { "" })
+    }
+}
+
+/// Every way the pokemon learns the moves matching the search, level up moves first then TMs and egg
+/// moves
+pub fn learned_moves(pokemon: &Pokemon, attack: &str, mode: MoveMatch) -> Vec<LearnedMove> {
+    let attack = mode.key(attack);
+    let learned = |name: &String, method: MoveLearn, is_new: bool| {
+        mode.matches(name, &attack).then(|| LearnedMove { name: name.clone(), method, is_new })
+    };
+
+    pokemon.lvl_up_moves.iter().filter_map(|m| learned(&m.name, MoveLearn::Level(m.lvl), m.is_new))
+        .chain(pokemon.tms.iter().filter_map(|m| learned(&m.name, MoveLearn::TM(m.num), m.is_new)))
+        .chain(pokemon.egg_moves.iter().filter_map(|m| learned(&m.name, MoveLearn::Egg, m.is_new)))
+        .collect()
+}
+
+/// A pokemon found by a move search and how it learns the move
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveLearner {
+    pub pokemon: Pokemon,
+    pub moves: Vec<LearnedMove>,
+}
+
+impl MoveLearner {
+    pub fn new(pokemon: Pokemon, attack: &str, mode: MoveMatch) -> Self {
+        let moves = learned_moves(&pokemon, attack, mode);
+        MoveLearner { pokemon, moves }
+    }
+
+    /// "Level 16, TM06" or, when the search matched several moves, the names too
+    pub fn methods(&self) -> String {
+        let several = self.moves.iter().any(|m| m.name != self.moves[0].name);
+        self.moves
+            .iter()
+            .map(|m| match several {
+                true => m.to_string(),
+                false => m.method.to_string(),
+            })
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
+
+impl fmt::Display for MoveLearner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\nLearns: {}", self.pokemon, self.methods())
+    }
+}
+
+/// One line for every way a pokemon learns the move, the columns are aligned:
+/// "001  Bulbasaur  Grass/Poison  TM  TM06  Toxic"
+pub fn learners_table(learners: &[MoveLearner]) -> String {
+    let mut rows = vec![["Dex", "Species", "Type", "Method", "Level/TM", "Move"].map(String::from).to_vec()];
+    for learner in learners {
+        let pokemon = &learner.pokemon;
+        for learned in &learner.moves {
+            rows.push(vec![
+                format!("{:03}", pokemon.dex_num),
+                pokemon.species.clone(),
+                pokemon.get_typing().to_string(),
+                learned.method.list().to_string(),
+                learned.method.detail(),
+                format!("{}{}", learned.name, if learned.is_new { " (!!)" } else { "" }),
+            ]);
+        }
+    }
+    aligned_table(&rows)
+}
+
+/// The rows as text, every column is as wide as its longest cell
+pub fn aligned_table(rows: &[Vec<String>]) -> String {
+    let columns = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..columns)
+        .map(|column| rows.iter().filter_map(|row| row.get(column)).map(|cell| cell.chars().count()).max().unwrap_or(0))
+        .collect();
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:width$}", cell, width = width))
+                .collect::<Vec<String>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_pokedex;
+
+    #[test]
+    fn learn_methods() {
+        let example = "068- Machamp\nStats: 90 HP/130 Atk/80 Def/65 SpA/85 SpD/55 Spe/505 BST\nType: Fighting\nAbilities: Guts/No Guard/Steadfast\nLocation:\n* Evolve Machoke (Trade)\nLevel Up:\n1: Fire Punch\n1: Thunder Punch\n44: Dynamic Punch (!!)\nTMs:\nTM01: Focus Punch\nEgg Moves:\nBullet Punch\n";
+        let (_, pokedex) = parse_pokedex(example).unwrap();
+        let machamp = &pokedex[0];
+
+        assert!(learned_moves(machamp, "Punch", MoveMatch::Exact).is_empty());
+        assert_eq!(learned_moves(machamp, "punch", MoveMatch::Partial).len(), 5);
+        assert_eq!(learned_moves(machamp, "Dynamic-Punch", MoveMatch::Exact).len(), 1);
+        assert_eq!(learned_moves(machamp, "Thunderpunch", MoveMatch::Exact).len(), 1);
+        assert_eq!(
+            learned_moves(machamp, "dynamic punch", MoveMatch::Exact),
+            [LearnedMove { name: String::from("Dynamic Punch"), method: MoveLearn::Level(44), is_new: true }]
+        );
+
+        let learner = MoveLearner::new(machamp.clone(), "focus punch", MoveMatch::Exact);
+        assert_eq!(learner.methods(), "TM01");
+        let learner = MoveLearner::new(machamp.clone(), "bullet", MoveMatch::Partial);
+        assert_eq!(learner.methods(), "Egg move");
+
+        let learner = MoveLearner::new(machamp.clone(), "fire punch", MoveMatch::Exact);
+        assert_eq!(
+            learners_table(&[learner]),
+            "Dex  Species  Type      Method    Level/TM  Move\n068  Machamp  Fighting  Level up  1         Fire Punch"
+        );
+    }
+}
@@ -3,9 +3,39 @@ pub mod parser;
 pub mod pokemon;
 pub mod backend;
 pub mod stats_bar;
+pub mod evolution;
+pub mod type_chart;
+pub mod config;
+pub mod store;
+pub mod diff;
+pub mod query;
+pub mod filters;
+pub mod fuzzy;
+pub mod search_index;
+pub mod learnset;
+pub mod moves;
+pub mod tm;
+pub mod ability;
+pub mod locations;
+pub mod stat_change;
 
 pub use typing::*;
 pub use parser::*;
 pub use pokemon::*;
 pub use backend::*;
 pub use stats_bar::*;
+pub use evolution::*;
+pub use type_chart::*;
+pub use config::*;
+pub use store::*;
+pub use diff::*;
+pub use query::*;
+pub use filters::*;
+pub use fuzzy::*;
+pub use search_index::*;
+pub use learnset::*;
+pub use moves::*;
+pub use tm::*;
+pub use ability::*;
+pub use locations::*;
+pub use stat_change::*;
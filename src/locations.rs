@@ -0,0 +1,233 @@
+/// This library inverts the "locations" of the pokedex: every area and the pokemon found there. The
+/// evolutions aren't areas, their "area" is the species evolving
+use std::fmt;
+
+use crate::{aligned_table, normalize, Encounter, EncounterMethod, Pokemon};
+
+/// A pokemon found in the area, the rate and qualifiers are the ones of its encounter
+#[derive(Debug, Clone, PartialEq)]
+pub struct AreaEncounter {
+    pub dex_num: u32,
+    pub species: String,
+    pub method: EncounterMethod,
+    pub rate: Option<u8>,
+    pub qualifiers: Vec<String>,
+}
+
+impl fmt::Display for AreaEncounter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut details = self.qualifiers.clone();
+        if let Some(rate) = self.rate {
+            details.push(format!("{}% chance", rate));
+        }
+        write!(f, "{:03} {}", self.dex_num, self.species)?;
+        if !details.is_empty() {
+            write!(f, " ({})", details.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// The encounters of the area in dex order
+#[derive(Debug, Clone, PartialEq)]
+pub struct Area {
+    pub name: String,
+    pub encounters: Vec<AreaEncounter>,
+}
+
+impl Area {
+    /// The encounters grouped by method, the most common pokemon first. Encounters without a rate
+    /// come last
+    pub fn by_method(&self) -> Vec<(EncounterMethod, Vec<&AreaEncounter>)> {
+        let mut groups: Vec<(EncounterMethod, Vec<&AreaEncounter>)> = Vec::new();
+        for encounter in &self.encounters {
+            match groups.iter_mut().find(|(method, _)| *method == encounter.method) {
+                Some((_, encounters)) => encounters.push(encounter),
+                None => groups.push((encounter.method, vec![encounter])),
+            }
+        }
+        groups.sort_by_key(|(method, _)| *method);
+        for (_, encounters) in &mut groups {
+            encounters.sort_by_key(|encounter| std::cmp::Reverse(encounter.rate));
+        }
+        groups
+    }
+}
+
+impl fmt::Display for Area {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        for (method, encounters) in self.by_method() {
+            write!(f, "\n\n{}:", method)?;
+            for encounter in encounters {
+                write!(f, "\n{}", encounter)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The start of the lines of the dex that are notes rather than places: "* Unavailable",
+/// "* Breed Manaphy" or "* Where It's Not Located: The Feebas Pool."
+const NOT_AREAS: [&str; 4] = ["unavailable", "breed ", "why ", "where "];
+
+/// Every area of the pokedex sorted by name. The names spelled differently are the same area,
+/// "Pokemon League" and "Pokémon League", and the subareas are part of their area: "Route 205 ~ South"
+/// is in "Route 205" with "South" as first qualifier
+pub fn areas(pokedex: &[Pokemon]) -> Vec<Area> {
+    let mut areas: Vec<Area> = Vec::new();
+    for pokemon in pokedex {
+        for location in pokemon.locations.iter().filter(|l| is_area(l)) {
+            let (name, subarea) = match location.area.split_once('~') {
+                Some((name, subarea)) => (name.trim(), Some(subarea.trim())),
+                None => (location.area.as_str(), None),
+            };
+            let mut qualifiers: Vec<String> = subarea.map(String::from).into_iter().collect();
+            qualifiers.extend(location.qualifiers.iter().cloned());
+            let encounter = AreaEncounter {
+                dex_num: pokemon.dex_num,
+                species: pokemon.species.clone(),
+                method: location.method,
+                rate: location.rate,
+                qualifiers,
+            };
+            let key = area_key(name);
+            match areas.iter_mut().find(|area| area_key(&area.name) == key) {
+                Some(area) => area.encounters.push(encounter),
+                None => areas.push(Area { name: name.to_string(), encounters: vec![encounter] }),
+            }
+        }
+    }
+    areas.sort_by(|a, b| a.name.cmp(&b.name));
+    areas
+}
+
+/// The evolutions aren't areas, neither are the lines of the dex only pointing at other lines,
+/// "* Evolution" or "* + see above", nor the notes of the dex
+fn is_area(location: &Encounter) -> bool {
+    let area = location.area.to_lowercase();
+    location.method != EncounterMethod::Evolution
+        && location.area != "Evolution"
+        && !location.area.starts_with('+')
+        && !(location.method == EncounterMethod::Other && NOT_AREAS.iter().any(|note| area.starts_with(note)))
+}
+
+/// The name of the area ignoring case, punctuation, accents and subarea: "Route 205 ~ South" and
+/// "route 205" have the same key
+fn area_key(area: &str) -> String {
+    let area = area.split_once('~').map_or(area, |(name, _)| name);
+    normalize(area)
+        .chars()
+        .map(|c| match c {
+            'à' | 'á' | 'â' | 'ä' | 'ã' => 'a',
+            'è' | 'é' | 'ê' | 'ë' => 'e',
+            'ì' | 'í' | 'î' | 'ï' => 'i',
+            'ò' | 'ó' | 'ô' | 'ö' | 'õ' => 'o',
+            'ù' | 'ú' | 'û' | 'ü' => 'u',
+            'ç' => 'c',
+            'ñ' => 'n',
+            c => c,
+        })
+        .collect()
+}
+
+/// Finds the area by name, ignoring case, punctuation, accents and subarea
+pub fn find_area(pokedex: &[Pokemon], area: &str) -> Option<Area> {
+    let key = area_key(area);
+    areas(pokedex).into_iter().find(|a| area_key(&a.name) == key)
+}
+
+/// One line for every encounter of the area grouped by method, the columns are aligned:
+/// "Grass  396  Starly  30%"
+pub fn area_table(area: &Area) -> String {
+    let mut rows = vec![["Method", "Dex", "Species", "Rate", "Details"].map(String::from).to_vec()];
+    for (method, encounters) in area.by_method() {
+        for encounter in encounters {
+            rows.push(vec![
+                method.to_string(),
+                format!("{:03}", encounter.dex_num),
+                encounter.species.clone(),
+                encounter.rate.map_or(String::new(), |rate| format!("{}%", rate)),
+                encounter.qualifiers.join(", "),
+            ]);
+        }
+    }
+    aligned_table(&rows)
+}
+
+/// The areas with gifts, static encounters, fossils or in-game trades, only those encounters are kept
+pub fn one_time_encounters(pokedex: &[Pokemon]) -> Vec<Area> {
+    areas(pokedex)
+        .into_iter()
+        .filter_map(|mut area| {
+            area.encounters.retain(|encounter| encounter.method.is_one_time());
+            (!area.encounters.is_empty()).then_some(area)
+        })
+        .collect()
+}
+
+/// One line for every encounter, the name of the area is only written on its first line:
+/// "Route 201  Gift  387  Turtwig  Starter"
+pub fn catalog_table(areas: &[Area]) -> String {
+    let mut rows = vec![["Area", "Method", "Dex", "Species", "Details"].map(String::from).to_vec()];
+    for area in areas {
+        for (i, encounter) in area.by_method().into_iter().flat_map(|(_, encounters)| encounters).enumerate() {
+            rows.push(vec![
+                if i == 0 { area.name.clone() } else { String::new() },
+                encounter.method.to_string(),
+                format!("{:03}", encounter.dex_num),
+                encounter.species.clone(),
+                encounter.qualifiers.join(", "),
+            ]);
+        }
+    }
+    aligned_table(&rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_pokedex;
+
+    #[test]
+    fn areas_by_method() {
+        let example = "396- Starly\nStats: 40 HP/55 Atk/30 Def/30 SpA/30 SpD/60 Spe/245 BST\nType: Normal/Flying\nAbilities: Keen Eye/Keen Eye/Reckless\nLocation:\n* Route 201 (10% chance)\n* Route 202 (Surfing, 5% chance)\n* Pokémon League (Surf, 10% chance)\nLevel Up:\n1: Tackle\nTMs:\nTM06: Toxic\nEgg Moves:\n\n397- Staravia\nStats: 55 HP/75 Atk/50 Def/40 SpA/40 SpD/80 Spe/340 BST\nType: Normal/Flying\nAbilities: Intimidate/Intimidate/Reckless\nLocation:\n* Evolve Starly (Level 14)\n* + see above\n* route 201 (30% chance)\n* Route 202 ~ South (Honey Tree, 20% chance)\n* Pokemon League (Surf, 30% chance)\n* Unavailable\nLevel Up:\n1: Tackle\nTMs:\nTM06: Toxic\nEgg Moves:\n";
+        let (_, pokedex) = parse_pokedex(example).unwrap();
+        let areas = areas(&pokedex);
+
+        let names: Vec<&str> = areas.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, ["Pokémon League", "Route 201", "Route 202"]);
+        assert_eq!(find_area(&pokedex, "pokemon league").unwrap().encounters.len(), 2);
+        let route = find_area(&pokedex, "ROUTE 201").unwrap();
+        assert_eq!(route.encounters.len(), 2);
+        let groups = route.by_method();
+        assert_eq!(groups.len(), 1);
+        let species: Vec<&str> = groups[0].1.iter().map(|e| e.species.as_str()).collect();
+        assert_eq!(species, ["Staravia", "Starly"]);
+        assert_eq!(
+            area_table(&route),
+            "Method  Dex  Species   Rate  Details\nGrass   397  Staravia  30%\nGrass   396  Starly    10%"
+        );
+        let route = find_area(&pokedex, "route 202 ~ south").unwrap();
+        assert_eq!(route.encounters[0].method, EncounterMethod::Surf);
+        assert_eq!(route.encounters[1].qualifiers, ["South"]);
+        assert!(find_area(&pokedex, "Starly").is_none());
+    }
+
+    #[test]
+    fn one_time_catalog() {
+        let example = "387- Turtwig\nStats: 55 HP/68 Atk/64 Def/45 SpA/55 SpD/31 Spe/318 BST\nType: Grass\nAbilities: Overgrow/Overgrow/Shell Armor\nLocation:\n* Route 201 (Starter, Gift)\n* Route 204 (10% chance)\nLevel Up:\n1: Tackle\nTMs:\nTM06: Toxic\nEgg Moves:\n\n138- Omanyte\nStats: 35 HP/40 Atk/100 Def/90 SpA/55 SpD/35 Spe/355 BST\nType: Rock/Water\nAbilities: Swift Swim/Shell Armor/Weak Armor\nLocation:\n* Oreburgh Mining Museum (Fossil Restoration)\n* Route 201 (Static Encounter)\nLevel Up:\n1: Constrict\nTMs:\nTM06: Toxic\nEgg Moves:\n";
+        let (_, pokedex) = parse_pokedex(example).unwrap();
+        let catalog = one_time_encounters(&pokedex);
+
+        let names: Vec<&str> = catalog.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, ["Oreburgh Mining Museum", "Route 201"]);
+        let expected = [
+            "Area                    Method              Dex  Species  Details",
+            "Oreburgh Mining Museum  Fossil Restoration  138  Omanyte",
+            "Route 201               Static Encounter    138  Omanyte",
+            "                        Gift                387  Turtwig  Starter",
+        ];
+        assert_eq!(catalog_table(&catalog), expected.join("\n"));
+    }
+}
@@ -1,46 +1,154 @@
 // hide console window on Windows in release
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
 use eframe::egui;
 use anyhow::Result;
 use poll_promise::Promise;
-use egui_extras::{image::RetainedImage};
-use std::{borrow::BorrowMut, fs, error::Error};
+use egui_extras::{image::RetainedImage, Column, TableBuilder};
+use std::{borrow::BorrowMut, collections::HashMap, fs, error::Error, path::{Path, PathBuf}, sync::Arc};
 use egui_dock::{DockArea, NodeIndex, Style, Tree};
 use egui::{RichText, FontFamily::*, FontId, TextStyle, ScrollArea, vec2};
 
-use lumi_dex::{stats_bar, typing_widget, Pokemon, backend::*};
+use lumi_dex::{DEFAULT_VERSION, AbilityHolder, AbilitySlot, holders_table, diff_dirs, diff_json, diff_markdown, diff_text, default_data_dir, default_db_path, matchups_widget, stats_bar, typing_widget, Encounter, EncounterMethod, EvolutionChain, Move, MoveCategory, MoveLearner, MoveMatch, MoveRegistry, learners_table, Area, area_table, catalog_table, PokedexStore, Pokemon, PokemonQuery, PokemonType, QueryFilter, QuerySort, SearchIndex, SearchKind, Stat, StatChanges, Storage, TmTable, query_filter, backend::*};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
-    let _ = connec_to_db().await?;
-
     // Parse the the arguments for the CLI
     let args = Args::parse();
 
-    if args.load_dex {
-        store_pokedex().await?;
+    // the diff works on the pokedex files and doesn't need the database
+    if let Some(Command::Diff { old_dir, new_dir, format, output }) = &args.command {
+        return diff(old_dir, new_dir, *format, output.as_deref());
+    }
+
+    let storage = match args.memory {
+        true => Storage::Memory,
+        false => Storage::File(args.db.clone().unwrap_or_else(default_db_path)),
+    };
+    let version = args.dex_version.clone().unwrap_or_else(|| DEFAULT_VERSION.to_string());
+    let surreal_store = SurrealStore::connect(&storage).await?.for_version(&version);
+
+    // the in memory database starts empty, so the pokedex is always loaded
+    if args.load_dex || args.memory {
+        let data_dir = args.data_dir.clone().unwrap_or_else(default_data_dir);
+        let summary = surreal_store.store_pokedex(&data_dir).await?;
+        println!("{}", summary);
     }
 
+    let store: Arc<dyn PokedexStore> = Arc::new(surreal_store);
+
     if let Some(species) = args.species {
-        match find_pokemon(&species).await? {
+        match store.find_pokemon(&species).await? {
             Some(pokemon) => {
                 fs::write("pokemons.txt", format!("{}", pokemon))?;
             },
-            None => {println!("Could not find {}", &species);},
+            None => {
+                println!("Could not find {}", &species);
+                let suggestions = store.suggest(&species, 5).await?;
+                if !suggestions.is_empty() {
+                    println!("Did you mean: {}?", suggestions.join(", "));
+                }
+            },
         }
         return Ok(());
     }
 
     if let Some(attack) = args.attack {
-        let pokemons: Vec<Pokemon> = find_by_move(&attack).await?;
-        // Map each pokemon into a string, collect that into a vector, then join each element
+        let mode = match args.partial {
+            true => MoveMatch::Partial,
+            false => MoveMatch::Exact,
+        };
+        let pokemons: Vec<MoveLearner> = store.find_move_learners(&attack, mode).await?;
+        let pokemons = match args.full {
+            // Map each pokemon into a string, collect that into a vector, then join each element
+            true => pokemons.iter().map(|x| format!("{}",x)).collect::<Vec<String>>().join("\n\n"),
+            false => learners_table(&pokemons),
+        };
+        fs::write("pokemons.txt", pokemons)?;
+        return Ok(());
+    }
+
+    if let Some(attack) = args.new_move {
+        let pokemons: Vec<Pokemon> = store.find_by_new_move(&attack).await?;
+        let pokemons = pokemons.iter().map(|x| format!("{}",x)).collect::<Vec<String>>().join("\n\n");
+        fs::write("pokemons.txt", pokemons)?;
+        return Ok(());
+    }
+
+    if let Some(ability) = args.ability {
+        let holders: Vec<AbilityHolder> = store.find_by_ability(&ability).await?;
+        fs::write("pokemons.txt", holders_table(&holders))?;
+        return Ok(());
+    }
+
+    if let Some(item) = args.item {
+        let pokemons: Vec<Pokemon> = store.find_by_held_item(&item).await?;
+        let pokemons = pokemons.iter().map(|x| format!("{}",x)).collect::<Vec<String>>().join("\n\n");
+        fs::write("pokemons.txt", pokemons)?;
+        return Ok(());
+    }
+
+    if let Some(pokemon_type) = args.pokemon_type {
+        let pokemons: Vec<Pokemon> = store.find_by_type(pokemon_type.parse()?).await?;
+        let pokemons = pokemons.iter().map(|x| format!("{}",x)).collect::<Vec<String>>().join("\n\n");
+        fs::write("pokemons.txt", pokemons)?;
+        return Ok(());
+    }
+
+    if let Some(Command::Query(query)) = &args.command {
+        let pokemons: Vec<Pokemon> = store.search(&query.query()).await?;
         let pokemons = pokemons.iter().map(|x| format!("{}",x)).collect::<Vec<String>>().join("\n\n");
         fs::write("pokemons.txt", pokemons)?;
         return Ok(());
     }
 
+    if let Some(Command::Tm { tm }) = &args.command {
+        match tm {
+            None => println!("{}", store.tm_table().await?),
+            Some(tm) => {
+                let pokemons: Vec<Pokemon> = store.find_by_tm(tm).await?;
+                let pokemons = pokemons.iter().map(|x| format!("{}",x)).collect::<Vec<String>>().join("\n\n");
+                fs::write("pokemons.txt", pokemons)?;
+            }
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Area { area }) = &args.command {
+        match area {
+            None => println!("{}", store.area_names().await?.join("\n")),
+            Some(area) => match store.find_by_area(area).await? {
+                Some(area) => fs::write("pokemons.txt", format!("{}\n{}", area.name, area_table(&area)))?,
+                None => println!("No area named \"{}\"", area),
+            },
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Gifts) = &args.command {
+        println!("{}", catalog_table(&store.one_time_encounters().await?));
+        return Ok(());
+    }
+
+    if let Some(Command::Moves { move_type, category }) = &args.command {
+        let moves: Vec<Move> = store.find_moves(*move_type, *category).await?;
+        let moves = moves.iter().map(|x| format!("{}",x)).collect::<Vec<String>>().join("\n\n");
+        fs::write("moves.txt", moves)?;
+        return Ok(());
+    }
+
+    let versions = store.versions().await?;
+    // the autocomplete index and the moves of every release are read once, typing in the search bar
+    // doesn't query the store
+    let mut releases = HashMap::new();
+    for version in versions.iter().map(String::as_str).chain([store.version()]) {
+        if !releases.contains_key(version) {
+            let release = store.with_version(version);
+            let data = ReleaseData { index: SearchIndex::new(&release.all().await?), moves: release.moves().await? };
+            releases.insert(version.to_string(), data);
+        }
+    }
     let options = eframe::NativeOptions {
         min_window_size: Some([690.0, 880.0].into()),
         ..Default::default()
@@ -48,7 +156,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
     eframe::run_native(
         "Pokemon Luminescent Platinum Pokedex",
         options,
-        Box::new(|cc| Box::new(MyApp::new(cc))),
+        Box::new(|cc| Box::new(MyApp::new(cc, store, versions, releases))),
     )?;
 
     Ok(())
@@ -57,22 +165,230 @@ async fn main() -> Result<(), Box<dyn Error>> {
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
 	#[arg(short, long)]
 	pub species: Option<String>,
 
+    /// Find the pokemon that learn this move and how they learn it
     #[arg(short, long)]
 	pub attack: Option<String>,
 
+    /// Match every move containing the --attack name instead of the whole name
+    #[arg(long, requires = "attack")]
+	pub partial: bool,
+
+    /// Write every detail of the --attack learners instead of the learnset table
+    #[arg(long, requires = "attack")]
+	pub full: bool,
+
+    /// Find the pokemon that gained this move in Lumi
+    #[arg(short, long)]
+	pub new_move: Option<String>,
+
+    /// Find the pokemon with this ability and the slot it's in
+    #[arg(long)]
+	pub ability: Option<String>,
+
+    /// Find the pokemon that can be found holding this item
+    #[arg(short, long)]
+	pub item: Option<String>,
+
+    /// Find the pokemon with this type in their Lumi typing
+    #[arg(short = 't', long = "type")]
+	pub pokemon_type: Option<String>,
+
     #[clap(long, short)]
 	pub load_dex: bool,
+
+    /// Path of the database file, defaults to the user data directory
+    #[arg(long, env = "LUMI_DEX_DB")]
+	pub db: Option<PathBuf>,
+
+    /// Directory with the pokedex text files, defaults to "./pokedex" or the user data directory
+    #[arg(long, env = "LUMI_DEX_DATA")]
+	pub data_dir: Option<PathBuf>,
+
+    /// Keep the database in memory, nothing is written to disk
+    #[arg(long, conflicts_with = "db")]
+	pub memory: bool,
+
+    /// Lumi release to query, or to import with --load-dex, defaults to the shipped dex version
+    #[arg(long, env = "LUMI_DEX_VERSION")]
+	pub dex_version: Option<String>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Compare two pokedex directories and list the changes of every pokemon
+    Diff {
+        old_dir: PathBuf,
+        new_dir: PathBuf,
+
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+
+        /// Write the changelog to this file instead of the terminal
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Find the pokemon matching every filter, the Lumi typing and stats are used
+    Query(QueryArgs),
+    /// Write the TM table, or the pokemon that can learn a TM given by number or name: "tm 36",
+    /// "tm Sludge Bomb"
+    Tm {
+        tm: Option<String>,
+    },
+    /// Write the areas of the dex, or the pokemon found in an area grouped by method: "area Route 201"
+    Area {
+        area: Option<String>,
+    },
+    /// Write the gifts, static encounters, fossils and in-game trades of the dex grouped by area
+    Gifts,
+    /// List the moves of the type and category, they need the details of the move data file
+    Moves {
+        #[arg(short = 't', long = "type")]
+        move_type: Option<PokemonType>,
+
+        #[arg(short, long)]
+        category: Option<MoveCategory>,
+    },
+}
+
+#[derive(clap::Args, Debug)]
+pub struct QueryArgs {
+    /// Type the pokemon must have, use it twice to ask for both types
+    #[arg(short = 't', long = "type")]
+    pub types: Vec<PokemonType>,
+
+    #[arg(short, long)]
+    pub ability: Option<String>,
+
+    /// Only match the ability when it's the hidden ability
+    #[arg(long, requires = "ability")]
+    pub hidden: bool,
+
+    /// Minimum value of a stat: "spe=100", "bst=500"
+    #[arg(long, value_name = "STAT=VALUE", value_parser = stat_bound)]
+    pub min: Vec<(Stat, u16)>,
+
+    /// Maximum value of a stat: "hp=60"
+    #[arg(long, value_name = "STAT=VALUE", value_parser = stat_bound)]
+    pub max: Vec<(Stat, u16)>,
+
+    /// Range of dex numbers: "1-151"
+    #[arg(long, value_name = "FIRST-LAST", value_parser = dex_range)]
+    pub dex: Option<(u32, u32)>,
+
+    /// Sort by "dex", "name" or a stat
+    #[arg(long, default_value = "dex")]
+    pub sort: QuerySort,
+
+    #[arg(long)]
+    pub descending: bool,
+}
+
+impl QueryArgs {
+    fn query(&self) -> PokemonQuery {
+        let mut query = PokemonQuery::new().sort_by(self.sort, self.descending);
+        for pokemon_type in &self.types {
+            query = query.with_type(*pokemon_type);
+        }
+        if let Some(ability) = &self.ability {
+            query = query.ability(ability);
+            if self.hidden {
+                query = query.hidden_ability_only();
+            }
+        }
+        for (stat, value) in &self.min {
+            query = query.min(*stat, *value);
+        }
+        for (stat, value) in &self.max {
+            query = query.max(*stat, *value);
+        }
+        if let Some((first, last)) = self.dex {
+            query = query.dex_range(first, last);
+        }
+        query
+    }
+}
+
+fn stat_bound(s: &str) -> Result<(Stat, u16), String> {
+    let (stat, value) = s.split_once('=').ok_or("expected STAT=VALUE")?;
+    let value = value.trim().parse().map_err(|_| format!("invalid stat value \"{}\"", value))?;
+    Ok((stat.parse()?, value))
+}
+
+fn dex_range(s: &str) -> Result<(u32, u32), String> {
+    let (first, last) = s.split_once('-').ok_or("expected FIRST-LAST")?;
+    let number = |n: &str| n.trim().parse::<u32>().map_err(|_| format!("invalid dex number \"{}\"", n));
+    Ok((number(first)?, number(last)?))
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum OutputFormat {
+    Text,
+    Markdown,
+    Json,
+}
+
+fn diff(old_dir: &Path, new_dir: &Path, format: OutputFormat, output: Option<&Path>) -> Result<(), Box<dyn Error>> {
+    let (diffs, skipped) = diff_dirs(old_dir, new_dir)?;
+    if !skipped.is_empty() {
+        eprintln!("Skipped {} entries that could not be parsed, they show up as added or removed:", skipped.len());
+        for error in &skipped {
+            eprintln!("{}", error);
+        }
+    }
+    let changelog = match format {
+        OutputFormat::Text => diff_text(&diffs),
+        OutputFormat::Markdown => diff_markdown(&diffs),
+        OutputFormat::Json => diff_json(&diffs)?,
+    };
+    match output {
+        Some(path) => fs::write(path, changelog)?,
+        None => println!("{}", changelog),
+    }
+
+    Ok(())
 }
 
 struct MyApp {
-    tree: Tree<TabContext>,
+    tree: Tree<DexTab>,
+    store: Arc<dyn PokedexStore>,
+    versions: Vec<String>,
+    releases: HashMap<String, ReleaseData>,
+    show_filters: bool,
+    filter: QueryFilter,
+    filter_results: Option<Promise<Result<Vec<Pokemon>>>>,
 }
 
 struct TabViewer<'a> {
     added_nodes: &'a mut Vec<NodeIndex>,
+    /// Tabs opened by the shown tabs, they're added to the focused leaf
+    opened: &'a mut Vec<DexTab>,
+    store: Arc<dyn PokedexStore>,
+    versions: &'a [String],
+    releases: &'a HashMap<String, ReleaseData>,
+}
+
+/// The autocomplete index and the moves of a Lumi release
+struct ReleaseData {
+    index: SearchIndex,
+    moves: MoveRegistry,
+}
+
+/// The pokemon tabs search a species, the learners and ability tabs list the pokemon learning a move
+/// or having an ability, the TM tabs browse the TM table, the area tabs the encounters of an area and
+/// the gift tabs the one-time encounters of every area
+enum DexTab {
+    Pokemon(Box<TabContext>),
+    Learners(LearnersTab),
+    Ability(AbilityTab),
+    Tms(TmTab),
+    Area(AreaTab),
+    Gifts(GiftsTab),
 }
 
 #[derive(Default)]
@@ -81,29 +397,322 @@ struct TabContext {
     pub search_text: String,
     pub pokemon: Option<Promise<Result<Option<Pokemon>>>>,
     pub pokemon_image: Option<Promise<Result<RetainedImage>>>,
+    pub evolutions: Option<Promise<Result<EvolutionChain>>>,
+    /// Species close to the search, only fetched when the search finds nothing
+    pub suggestions: Option<Promise<Result<Vec<String>>>>,
+    /// Completion highlighted with the arrow keys
+    pub completion: Option<usize>,
+    pub location_sort: LocationSort,
+    pub location_descending: bool,
+    /// Lumi release searched by the tab, None searches the release of the store
+    pub version: Option<String>,
+}
+
+impl TabContext {
+    fn store(&self, store: &Arc<dyn PokedexStore>) -> Arc<dyn PokedexStore> {
+        match &self.version {
+            Some(version) => store.with_version(version),
+            None => store.clone(),
+        }
+    }
+
+    /// A new tab searching the species
+    fn with_search(store: &Arc<dyn PokedexStore>, species: String, version: Option<String>) -> Self {
+        let mut tab = TabContext {
+            search_text: species,
+            version,
+            ..Default::default()
+        };
+        tab.search(store);
+        tab
+    }
+
+    /// Looks up the species in the search bar, the image and evolutions are fetched again once the
+    /// pokemon is found
+    fn search(&mut self, store: &Arc<dyn PokedexStore>) {
+        self.pokemon = None;
+        self.pokemon_image = None;
+        self.evolutions = None;
+        self.suggestions = None;
+        let search = self.search_text.clone();
+        let store = self.store(store);
+        self.pokemon = Some(Promise::spawn_async(async move {
+            store.find_pokemon(&search).await
+        }));
+        self.searched = true;
+    }
+}
+
+struct LearnersTab {
+    pub attack: String,
+    /// Lumi release of the tab that opened it, the pokemon clicked are opened in the same release
+    pub version: Option<String>,
+    pub learners: Promise<Result<Vec<MoveLearner>>>,
+}
+
+impl LearnersTab {
+    fn new(store: Arc<dyn PokedexStore>, attack: &str, version: Option<String>) -> Self {
+        let search = attack.to_string();
+        LearnersTab {
+            attack: attack.to_string(),
+            version,
+            learners: Promise::spawn_async(async move {
+                store.find_move_learners(&search, MoveMatch::Exact).await
+            }),
+        }
+    }
+}
+
+struct AbilityTab {
+    pub ability: String,
+    /// Lumi release of the tab that opened it, the pokemon clicked are opened in the same release
+    pub version: Option<String>,
+    pub holders: Promise<Result<Vec<AbilityHolder>>>,
+}
+
+impl AbilityTab {
+    fn new(store: Arc<dyn PokedexStore>, ability: &str, version: Option<String>) -> Self {
+        let search = ability.to_string();
+        AbilityTab {
+            ability: ability.to_string(),
+            version,
+            holders: Promise::spawn_async(async move { store.find_by_ability(&search).await }),
+        }
+    }
+}
+
+struct TmTab {
+    pub table: Promise<Result<TmTable>>,
+    pub selected: Option<u16>,
+    /// Lumi release of the table, the pokemon clicked are opened in the same release
+    pub version: Option<String>,
+}
+
+impl TmTab {
+    fn new(store: Arc<dyn PokedexStore>) -> Self {
+        TmTab {
+            table: Promise::spawn_async(async move { store.tm_table().await }),
+            selected: None,
+            version: None,
+        }
+    }
+}
+
+struct AreaTab {
+    pub areas: Promise<Result<Vec<String>>>,
+    pub selected: Option<String>,
+    pub encounters: Option<Promise<Result<Option<Area>>>>,
+    /// Lumi release of the tab that opened it, the pokemon clicked are opened in the same release
+    pub version: Option<String>,
+}
+
+impl AreaTab {
+    /// A new tab showing the area, or only the area picker
+    fn new(store: Arc<dyn PokedexStore>, area: Option<String>, version: Option<String>) -> Self {
+        let names = store.clone();
+        let mut tab = AreaTab {
+            areas: Promise::spawn_async(async move { names.area_names().await }),
+            selected: None,
+            encounters: None,
+            version,
+        };
+        if let Some(area) = area {
+            tab.select(store, area);
+        }
+        tab
+    }
+
+    fn store(&self, store: &Arc<dyn PokedexStore>) -> Arc<dyn PokedexStore> {
+        match &self.version {
+            Some(version) => store.with_version(version),
+            None => store.clone(),
+        }
+    }
+
+    fn select(&mut self, store: Arc<dyn PokedexStore>, area: String) {
+        let search = area.clone();
+        self.encounters = Some(Promise::spawn_async(async move { store.find_by_area(&search).await }));
+        self.selected = Some(area);
+    }
+}
+
+struct GiftsTab {
+    pub catalog: Promise<Result<Vec<Area>>>,
+    /// Lumi release of the catalog, the pokemon and areas clicked are opened in the same release
+    pub version: Option<String>,
+}
+
+impl GiftsTab {
+    fn new(store: Arc<dyn PokedexStore>) -> Self {
+        GiftsTab {
+            catalog: Promise::spawn_async(async move { store.one_time_encounters().await }),
+            version: None,
+        }
+    }
+}
+
+/// Column used to sort the locations table, "Dex" keeps the order of the pokedex
+#[derive(Default, Clone, Copy, PartialEq)]
+enum LocationSort {
+    #[default]
+    Dex,
+    Area,
+    Method,
+    Rate,
+}
+
+fn sort_locations(locations: &mut [Encounter], sort: LocationSort, descending: bool) {
+    match sort {
+        LocationSort::Dex => {},
+        LocationSort::Area => locations.sort_by(|a, b| a.area.cmp(&b.area)),
+        LocationSort::Method => locations.sort_by_key(|a| a.method),
+        LocationSort::Rate => locations.sort_by_key(|a| a.rate),
+    }
+    if descending {
+        locations.reverse();
+    }
 }
 
 impl egui_dock::TabViewer for TabViewer<'_> {
-    type Tab = TabContext;
+    type Tab = DexTab;
 
     fn ui(&mut self, ui: &mut egui::Ui, tab: &mut Self::Tab) {
-        let pokemon_promise = tab.pokemon.get_or_insert_with(|| Promise::spawn_async(async move {Ok(None)}));
+        match tab {
+            DexTab::Pokemon(tab) => self.pokemon_ui(ui, tab),
+            DexTab::Learners(tab) => self.learners_ui(ui, tab),
+            DexTab::Ability(tab) => self.ability_ui(ui, tab),
+            DexTab::Tms(tab) => self.tms_ui(ui, tab),
+            DexTab::Area(tab) => self.area_ui(ui, tab),
+            DexTab::Gifts(tab) => self.gifts_ui(ui, tab),
+        }
+    }
+
+    fn title(&mut self, tab: &mut Self::Tab) -> egui::WidgetText {
+        match tab {
+            DexTab::Pokemon(_) => format!("Tab").into(),
+            DexTab::Learners(tab) => format!("Learners: {}", tab.attack).into(),
+            DexTab::Ability(tab) => format!("Ability: {}", tab.ability).into(),
+            DexTab::Tms(_) => "TMs".into(),
+            DexTab::Area(tab) => match &tab.selected {
+                Some(area) => area.clone().into(),
+                None => "Areas".into(),
+            },
+            DexTab::Gifts(_) => "Gifts".into(),
+        }
+    }
+
+    fn on_add(&mut self, node: NodeIndex) {
+        self.added_nodes.push(node);
+    }
+}
+
+impl<'a> TabViewer<'a> {
+    /// The data of the release searched by a tab, None is the release of the store
+    fn release(&self, version: Option<&str>) -> &'a ReleaseData {
+        let releases = self.releases;
+        version
+            .and_then(|version| releases.get(version))
+            .or_else(|| releases.get(self.store.version()))
+            .expect("the release of the store is read at startup")
+    }
+
+    fn pokemon_ui(&mut self, ui: &mut egui::Ui, tab: &mut TabContext) {
+        // species clicked in the evolution strip, it's searched once the tab is drawn
+        let mut clicked: Option<String> = None;
+        // move clicked in the learnsets, its learners are opened in a new tab
+        let mut clicked_move: Option<String> = None;
+        // ability clicked in the pokemon info, the pokemon with it are opened in a new tab
+        let mut clicked_ability: Option<String> = None;
+        // area clicked in the locations, its encounters are opened in a new tab
+        let mut clicked_area: Option<String> = None;
+        let release = self.release(tab.version.as_deref());
+        let moves = &release.moves;
 
         let search_bar_size = vec2(ui.available_width(), ui.available_height() * 0.05);
         let search_bar_layout = egui::Layout::right_to_left(egui::Align::Min);
         ui.allocate_ui_with_layout(search_bar_size, search_bar_layout, |ui| {
             ui.label("🔍");
-            let response = ui.add(egui::TextEdit::singleline(&mut tab.search_text));
-            if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-                tab.pokemon_image = None;
-                let search = tab.search_text.clone();
-                *pokemon_promise = Promise::spawn_async(async move {
-                    find_pokemon(&search).await
+            let search_id = ui.make_persistent_id("search");
+            let popup_id = search_id.with("completions");
+            let completions = match ui.memory(|m| m.is_popup_open(popup_id)) {
+                true => release.index.complete(&tab.search_text, 8),
+                false => Vec::new(),
+            };
+
+            // the arrows are taken before the text edit sees them
+            if !completions.is_empty() && ui.memory(|m| m.has_focus(search_id)) {
+                let (down, up) = ui.input_mut(|i| {
+                    (i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown), i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp))
+                });
+                let last = completions.len() - 1;
+                tab.completion = match (down, up, tab.completion) {
+                    (true, _, None) => Some(0),
+                    (true, _, Some(i)) => Some((i + 1).min(last)),
+                    (_, true, Some(i)) => i.min(last).checked_sub(1),
+                    (_, _, completion) => completion.filter(|i| *i <= last),
+                };
+            }
+
+            let response = ui.add(egui::TextEdit::singleline(&mut tab.search_text).id(search_id));
+            if response.changed() {
+                tab.completion = None;
+                ui.memory_mut(|m| m.open_popup(popup_id));
+            }
+            let mut search = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if search {
+                if let Some(completion) = tab.completion.and_then(|i| completions.get(i)) {
+                    tab.search_text = completion.text();
+                }
+            }
+
+            if !completions.is_empty() && !search {
+                egui::popup_below_widget(ui, popup_id, &response, |ui| {
+                    for (i, completion) in completions.iter().enumerate() {
+                        if ui.selectable_label(tab.completion == Some(i), completion.to_string()).clicked() {
+                            tab.search_text = completion.text();
+                            search = true;
+                        }
+                    }
                 });
-                tab.searched = true;
+            }
+            if search {
+                tab.completion = None;
+                ui.memory_mut(|m| m.close_popup());
+            }
+            // the release picker is only needed when more than one release was imported
+            if self.versions.len() > 1 {
+                let selected = tab.version.clone().unwrap_or_else(|| self.store.version().to_string());
+                egui::ComboBox::from_id_source(ui.make_persistent_id("version"))
+                    .selected_text(format!("Lumi {}", selected))
+                    .show_ui(ui, |ui| {
+                        for version in self.versions {
+                            if ui.selectable_label(version == &selected, version).clicked() && version != &selected {
+                                tab.version = Some(version.clone());
+                                search = !tab.search_text.is_empty();
+                            }
+                        }
+                    });
+            }
+
+            // the move and ability searches open a tab listing the pokemon, the pokemon tab keeps its
+            // pokemon
+            if search {
+                let store = tab.store(&self.store);
+                match SearchKind::parse(&tab.search_text) {
+                    (SearchKind::Species, _) => tab.search(&self.store),
+                    (SearchKind::Move, attack) => {
+                        self.opened.push(DexTab::Learners(LearnersTab::new(store, attack, tab.version.clone())));
+                    }
+                    (SearchKind::Ability, ability) => {
+                        self.opened.push(DexTab::Ability(AbilityTab::new(store, ability, tab.version.clone())));
+                    }
+                }
             }
         });
 
+        let store = tab.store(&self.store);
+        let pokemon_promise = tab.pokemon.get_or_insert_with(|| Promise::spawn_async(async move {Ok(None)}));
+
         match pokemon_promise.ready(){
             None => {
                 ui.horizontal_centered(|ui| {
@@ -117,13 +726,30 @@ impl egui_dock::TabViewer for TabViewer<'_> {
             Some(Ok(None)) => {
                 if tab.searched {
                     ui.colored_label(ui.visuals().error_fg_color, format!("Did not find {}", &tab.search_text));
-                    tab.searched = false;
+
+                    let search = tab.search_text.clone();
+                    let suggestions = tab.suggestions.get_or_insert_with(|| Promise::spawn_async(async move { store.suggest(&search, 5).await }));
+                    if let Some(Ok(suggestions)) = suggestions.ready() {
+                        if !suggestions.is_empty() {
+                            ui.horizontal_wrapped(|ui| {
+                                ui.label("Did you mean…");
+                                for species in suggestions {
+                                    if ui.button(species).clicked() {
+                                        clicked = Some(species.clone());
+                                    }
+                                }
+                            });
+                        }
+                    }
                 }
             }
             Some(Ok(Some(result))) => {
                 let pokemon = result.clone();
                 let species = pokemon.species.clone().replace(" ","-").replace(".","");
                 let promise = tab.pokemon_image.get_or_insert_with(|| Promise::spawn_async(async move { get_image(&species).await })).borrow_mut();
+                let species = pokemon.species.clone();
+                let store = store.clone();
+                let evolutions = tab.evolutions.get_or_insert_with(|| Promise::spawn_async(async move { store.find_evolution_chain(&species).await }));
 
                 // Display main pokemon info
                 let general_info_size = vec2(ui.available_width(), ui.available_height() * 0.45);
@@ -142,16 +768,59 @@ impl egui_dock::TabViewer for TabViewer<'_> {
                     }
 
                     ui.vertical(|ui| {
-                        let _dex_num_label = ui.label(format!("National № {:04}", pokemon.dex_num));
+                        let _dex_num_label = ui.label(format!("National № {:04} · Lumi {}", pokemon.dex_num, pokemon.version));
                         ui.horizontal(|ui| {
                             let _pokemon_species_label = ui.hyperlink_to(RichText::new(&pokemon.species).heading(), format!("https://pokemondb.net/pokedex/{}", &pokemon.species.to_lowercase()));
-                            let _pokemon_type_label = match &pokemon.new_pokemon_type {
-                                Some(new_type) => {ui.add(typing_widget(&new_type));},
-                                None => {ui.add(typing_widget(&pokemon.pokemon_type));},
-                            };
+                            let _pokemon_type_label = ui.add(typing_widget(pokemon.get_typing()));
                         });
-                        let _pokemon_abilities_label = ui.label(format!("Abilities: \n\t\t{} (hidden ability)", pokemon.abilities.join("\n\t\t")));
-                        ui.add(stats_bar(&pokemon.get_stats()));
+                        if let Some(Ok(chain)) = evolutions.ready() {
+                            if chain.stages.len() > 1 {
+                                ui.horizontal_wrapped(|ui| {
+                                    for (i, stage) in chain.stages.iter().enumerate() {
+                                        if i > 0 {
+                                            ui.label("→");
+                                        }
+                                        for species in stage {
+                                            let mut response = ui.selectable_label(species == &pokemon.species, species);
+                                            if let Some(evolution) = chain.evolution_to(species) {
+                                                response = response.on_hover_text(&evolution.condition);
+                                            }
+                                            if response.clicked() {
+                                                clicked = Some(species.clone());
+                                            }
+                                        }
+                                    }
+                                });
+                            }
+                        }
+                        ui.label("Abilities:");
+                        for (slot, ability) in pokemon.abilities.iter() {
+                            let text = match slot {
+                                AbilitySlot::Hidden => format!("\t\t{} (hidden ability)", ability),
+                                _ => format!("\t\t{}", ability),
+                            };
+                            if ui.add(egui::Label::new(text).sense(egui::Sense::click())).clicked() {
+                                clicked_ability = Some(ability.clone());
+                            }
+                        }
+                        if !pokemon.held_items.is_empty() {
+                            let held_items = pokemon.held_items.iter().map(|x| format!("{}", x)).collect::<Vec<String>>().join("\n\t\t");
+                            ui.label(format!("Wild held items: \n\t\t{}", held_items));
+                        }
+                        ui.add(stats_bar(&StatChanges::of(&pokemon)));
+                    });
+
+                    ui.separator();
+
+                    ui.vertical(|ui| {
+                        ui.strong("Damage taken");
+                        // the abilities changing a matchup get their own profile
+                        for (ability, profile) in pokemon.defensive_profiles() {
+                            if let Some(ability) = ability {
+                                ui.label(format!("With {}", ability));
+                            }
+                            ui.add(matchups_widget(&profile));
+                        }
                     });
                 });
 
@@ -167,7 +836,9 @@ impl egui_dock::TabViewer for TabViewer<'_> {
                             .show(ui, |ui| {
                                 ui.vertical(|ui| {
                                     for attack in pokemon.lvl_up_moves{
-                                        ui.label(format!("{}",attack));
+                                        if move_label(ui, format!("{}",attack), attack.is_new, moves.get(&attack.name)).clicked() {
+                                            clicked_move = Some(attack.name.clone());
+                                        }
                                     }
                                 });
                             });
@@ -184,7 +855,9 @@ impl egui_dock::TabViewer for TabViewer<'_> {
                             .show(ui, |ui| {
                                 ui.vertical(|ui| {
                                     for attack in pokemon.tms{
-                                        ui.label(format!("{}",attack));
+                                        if move_label(ui, format!("{}",attack), attack.is_new, moves.get(&attack.name)).clicked() {
+                                            clicked_move = Some(attack.name.clone());
+                                        }
                                     }
                                 });
                             });
@@ -201,7 +874,9 @@ impl egui_dock::TabViewer for TabViewer<'_> {
                             .show(ui, |ui| {
                                 ui.vertical(|ui| {
                                     for attack in pokemon.egg_moves{
-                                        ui.label(format!("{}",attack));
+                                        if move_label(ui, format!("{}",attack), attack.is_new, moves.get(&attack.name)).clicked() {
+                                            clicked_move = Some(attack.name.clone());
+                                        }
                                     }
                                 });
                             });
@@ -213,14 +888,64 @@ impl egui_dock::TabViewer for TabViewer<'_> {
                     ui.vertical(|ui| {
                         ui.strong("Locations");
                         ui.push_id(4, |ui| {
-                            ScrollArea::vertical()
-                            .max_height(ui.available_height())
-                            .show(ui, |ui| {
-                                ui.vertical(|ui| {
-                                    for location in pokemon.locations{
-                                        ui.label(format!("{}",location));
-                                    }
+                            let mut locations = pokemon.locations.clone();
+                            sort_locations(&mut locations, tab.location_sort, tab.location_descending);
+
+                            let height = ui.available_height();
+                            TableBuilder::new(ui)
+                            .striped(true)
+                            .max_scroll_height(height)
+                            .column(Column::auto())
+                            .column(Column::auto())
+                            .column(Column::auto())
+                            .column(Column::remainder())
+                            .header(20.0, |mut header| {
+                                for (column, name) in [(LocationSort::Area, "Area"), (LocationSort::Method, "Method"), (LocationSort::Rate, "Rate")] {
+                                    header.col(|ui| {
+                                        let selected = tab.location_sort == column;
+                                        let arrow = match (selected, tab.location_descending) {
+                                            (true, false) => " ⏶",
+                                            (true, true) => " ⏷",
+                                            (false, _) => "",
+                                        };
+                                        if ui.selectable_label(selected, RichText::new(format!("{}{}", name, arrow)).strong()).clicked() {
+                                            tab.location_descending = selected && !tab.location_descending;
+                                            tab.location_sort = column;
+                                        }
+                                    });
+                                }
+                                header.col(|ui| {
+                                    ui.strong("Details");
                                 });
+                            })
+                            .body(|mut body| {
+                                for location in locations {
+                                    body.row(20.0, |mut row| {
+                                        row.col(|ui| {
+                                            match location.method {
+                                                EncounterMethod::Evolution => {
+                                                    ui.label(&location.area);
+                                                }
+                                                _ => {
+                                                    if ui.selectable_label(false, &location.area).clicked() {
+                                                        clicked_area = Some(location.area.clone());
+                                                    }
+                                                }
+                                            }
+                                        });
+                                        row.col(|ui| {
+                                            ui.label(format!("{}", location.method));
+                                        });
+                                        row.col(|ui| {
+                                            if let Some(rate) = location.rate {
+                                                ui.label(format!("{}%", rate));
+                                            }
+                                        });
+                                        row.col(|ui| {
+                                            ui.label(location.qualifiers.join(", "));
+                                        });
+                                    });
+                                }
                             });
                         });
                     });
@@ -228,19 +953,378 @@ impl egui_dock::TabViewer for TabViewer<'_> {
                 });
             }
         }
+
+        if let Some(species) = clicked {
+            tab.search_text = species;
+            tab.search(&self.store);
+        }
+        if let Some(attack) = clicked_move {
+            self.opened.push(DexTab::Learners(LearnersTab::new(tab.store(&self.store), &attack, tab.version.clone())));
+        }
+        if let Some(ability) = clicked_ability {
+            self.opened.push(DexTab::Ability(AbilityTab::new(tab.store(&self.store), &ability, tab.version.clone())));
+        }
+        if let Some(area) = clicked_area {
+            self.opened.push(DexTab::Area(AreaTab::new(tab.store(&self.store), Some(area), tab.version.clone())));
+        }
     }
 
-    fn title(&mut self, _tab: &mut Self::Tab) -> egui::WidgetText {
-        format!("Tab").into()
+    /// Table of the pokemon learning the move, clicking a pokemon opens it in a new tab
+    fn learners_ui(&mut self, ui: &mut egui::Ui, tab: &mut LearnersTab) {
+        ui.heading(format!("Learners of {}", tab.attack));
+        if let Some(details) = self.release(tab.version.as_deref()).moves.get(&tab.attack).and_then(|m| m.details.as_ref()) {
+            ui.label(details.to_string());
+        }
+
+        let mut clicked: Option<String> = None;
+        match tab.learners.ready() {
+            None => {
+                ui.spinner();
+            }
+            Some(Err(err)) => {
+                ui.colored_label(ui.visuals().error_fg_color, format!("{}", err));
+            }
+            Some(Ok(learners)) => {
+                ui.label(format!("{} pokemon", learners.len()));
+                let height = ui.available_height();
+                TableBuilder::new(ui)
+                .striped(true)
+                .max_scroll_height(height)
+                .column(Column::auto())
+                .column(Column::auto())
+                .column(Column::auto())
+                .column(Column::remainder())
+                .header(20.0, |mut header| {
+                    for name in ["Dex", "Species", "Type", "Learnt by"] {
+                        header.col(|ui| {
+                            ui.strong(name);
+                        });
+                    }
+                })
+                .body(|mut body| {
+                    for learner in learners {
+                        body.row(24.0, |mut row| {
+                            row.col(|ui| {
+                                ui.label(format!("{:03}", learner.pokemon.dex_num));
+                            });
+                            row.col(|ui| {
+                                if ui.selectable_label(false, &learner.pokemon.species).clicked() {
+                                    clicked = Some(learner.pokemon.species.clone());
+                                }
+                            });
+                            row.col(|ui| {
+                                ui.add(typing_widget(learner.pokemon.get_typing()));
+                            });
+                            row.col(|ui| {
+                                ui.label(learner.methods());
+                            });
+                        });
+                    }
+                });
+            }
+        }
+
+        if let Some(species) = clicked {
+            let pokemon_tab = TabContext::with_search(&self.store, species, tab.version.clone());
+            self.opened.push(DexTab::Pokemon(Box::new(pokemon_tab)));
+        }
     }
 
-    fn on_add(&mut self, node: NodeIndex) {
-        self.added_nodes.push(node);
+    /// Table of the pokemon with the ability and the slot it's in, clicking a pokemon opens it in a new
+    /// tab
+    fn ability_ui(&mut self, ui: &mut egui::Ui, tab: &mut AbilityTab) {
+        ui.heading(format!("Pokemon with {}", tab.ability));
+
+        let mut clicked: Option<String> = None;
+        match tab.holders.ready() {
+            None => {
+                ui.spinner();
+            }
+            Some(Err(err)) => {
+                ui.colored_label(ui.visuals().error_fg_color, format!("{}", err));
+            }
+            Some(Ok(holders)) => {
+                ui.label(format!("{} pokemon", holders.len()));
+                let height = ui.available_height();
+                TableBuilder::new(ui)
+                .striped(true)
+                .max_scroll_height(height)
+                .column(Column::auto())
+                .column(Column::auto())
+                .column(Column::auto())
+                .column(Column::remainder())
+                .header(20.0, |mut header| {
+                    for name in ["Dex", "Species", "Type", "Slot"] {
+                        header.col(|ui| {
+                            ui.strong(name);
+                        });
+                    }
+                })
+                .body(|mut body| {
+                    for holder in holders {
+                        body.row(24.0, |mut row| {
+                            row.col(|ui| {
+                                ui.label(format!("{:03}", holder.pokemon.dex_num));
+                            });
+                            row.col(|ui| {
+                                if ui.selectable_label(false, &holder.pokemon.species).clicked() {
+                                    clicked = Some(holder.pokemon.species.clone());
+                                }
+                            });
+                            row.col(|ui| {
+                                ui.add(typing_widget(holder.pokemon.get_typing()));
+                            });
+                            row.col(|ui| {
+                                ui.label(holder.slot_names());
+                            });
+                        });
+                    }
+                });
+            }
+        }
+
+        if let Some(species) = clicked {
+            let pokemon_tab = TabContext::with_search(&self.store, species, tab.version.clone());
+            self.opened.push(DexTab::Pokemon(Box::new(pokemon_tab)));
+        }
+    }
+
+    /// The TM table of the release, picking a TM lists the pokemon that can learn it
+    fn tms_ui(&mut self, ui: &mut egui::Ui, tab: &mut TmTab) {
+        let release = self.release(tab.version.as_deref());
+        let mut clicked: Option<String> = None;
+        match tab.table.ready() {
+            None => {
+                ui.spinner();
+            }
+            Some(Err(err)) => {
+                ui.colored_label(ui.visuals().error_fg_color, format!("{}", err));
+            }
+            Some(Ok(table)) => {
+                if !table.conflicts.is_empty() {
+                    let conflicts = table.conflicts.iter().map(|c| c.to_string()).collect::<Vec<String>>().join("\n");
+                    ui.colored_label(ui.visuals().warn_fg_color, format!("{} TM conflicts in the dex", table.conflicts.len()))
+                        .on_hover_text(conflicts);
+                }
+
+                ui.horizontal_top(|ui| {
+                    ui.push_id(0, |ui| {
+                        ScrollArea::vertical().show(ui, |ui| {
+                            ui.vertical(|ui| {
+                                for tm in &table.entries {
+                                    let mut response = ui.selectable_label(tab.selected == Some(tm.num), tm.to_string());
+                                    if let Some(details) = release.moves.get(&tm.name).and_then(|m| m.details.as_ref()) {
+                                        response = response.on_hover_text(details.to_string());
+                                    }
+                                    if response.clicked() {
+                                        tab.selected = Some(tm.num);
+                                    }
+                                }
+                            });
+                        });
+                    });
+
+                    ui.separator();
+
+                    ui.vertical(|ui| {
+                        let Some(tm) = tab.selected.and_then(|num| table.get(num)) else {
+                            ui.label("Pick a TM to list the pokemon that can learn it");
+                            return;
+                        };
+                        ui.heading(tm.to_string());
+                        ui.label(format!("{} pokemon", tm.learners.len()));
+                        ui.push_id(1, |ui| {
+                            ScrollArea::vertical().show(ui, |ui| {
+                                for species in &tm.learners {
+                                    if ui.selectable_label(false, species).clicked() {
+                                        clicked = Some(species.clone());
+                                    }
+                                }
+                            });
+                        });
+                    });
+                });
+            }
+        }
+
+        if let Some(species) = clicked {
+            let pokemon_tab = TabContext::with_search(&self.store, species, tab.version.clone());
+            self.opened.push(DexTab::Pokemon(Box::new(pokemon_tab)));
+        }
+    }
+
+    /// Area picker and the encounters of the area grouped by method, the most common pokemon first.
+    /// Clicking a pokemon opens it in a new tab
+    fn area_ui(&mut self, ui: &mut egui::Ui, tab: &mut AreaTab) {
+        let mut picked: Option<String> = None;
+        match tab.areas.ready() {
+            None => {
+                ui.spinner();
+            }
+            Some(Err(err)) => {
+                ui.colored_label(ui.visuals().error_fg_color, format!("{}", err));
+            }
+            Some(Ok(areas)) => {
+                egui::ComboBox::from_id_source("area")
+                    .selected_text(tab.selected.as_deref().unwrap_or("Pick an area"))
+                    .width(ui.available_width() * 0.5)
+                    .show_ui(ui, |ui| {
+                        for area in areas {
+                            if ui.selectable_label(tab.selected.as_ref() == Some(area), area).clicked() {
+                                picked = Some(area.clone());
+                            }
+                        }
+                    });
+            }
+        }
+        if let Some(area) = picked {
+            tab.select(tab.store(&self.store), area);
+        }
+
+        let mut clicked: Option<String> = None;
+        match tab.encounters.as_ref().map(|encounters| encounters.ready()) {
+            None => {
+                ui.label("Pick an area to list the pokemon found there");
+            }
+            Some(None) => {
+                ui.spinner();
+            }
+            Some(Some(Err(err))) => {
+                ui.colored_label(ui.visuals().error_fg_color, format!("{}", err));
+            }
+            Some(Some(Ok(None))) => {
+                ui.label("No pokemon found in this area");
+            }
+            Some(Some(Ok(Some(area)))) => {
+                ui.heading(&area.name);
+                ScrollArea::vertical().show(ui, |ui| {
+                    for (method, encounters) in area.by_method() {
+                        ui.separator();
+                        ui.strong(method.to_string());
+                        egui::Grid::new(method.to_string()).striped(true).show(ui, |ui| {
+                            for encounter in encounters {
+                                ui.label(format!("{:03}", encounter.dex_num));
+                                if ui.selectable_label(false, &encounter.species).clicked() {
+                                    clicked = Some(encounter.species.clone());
+                                }
+                                ui.label(encounter.rate.map_or(String::new(), |rate| format!("{}%", rate)));
+                                ui.label(encounter.qualifiers.join(", "));
+                                ui.end_row();
+                            }
+                        });
+                    }
+                });
+            }
+        }
+
+        if let Some(species) = clicked {
+            let pokemon_tab = TabContext::with_search(&self.store, species, tab.version.clone());
+            self.opened.push(DexTab::Pokemon(Box::new(pokemon_tab)));
+        }
+    }
+
+    /// The one-time encounters grouped by area, clicking a pokemon opens it and clicking an area
+    /// opens all of its encounters
+    fn gifts_ui(&mut self, ui: &mut egui::Ui, tab: &mut GiftsTab) {
+        let mut clicked: Option<String> = None;
+        let mut clicked_area: Option<String> = None;
+        match tab.catalog.ready() {
+            None => {
+                ui.spinner();
+            }
+            Some(Err(err)) => {
+                ui.colored_label(ui.visuals().error_fg_color, format!("{}", err));
+            }
+            Some(Ok(catalog)) => {
+                let count: usize = catalog.iter().map(|area| area.encounters.len()).sum();
+                ui.label(format!("{} one-time encounters in {} areas", count, catalog.len()));
+                ScrollArea::vertical().show(ui, |ui| {
+                    for area in catalog {
+                        ui.separator();
+                        if ui.selectable_label(false, RichText::new(&area.name).strong()).clicked() {
+                            clicked_area = Some(area.name.clone());
+                        }
+                        egui::Grid::new(&area.name).striped(true).show(ui, |ui| {
+                            for (method, encounters) in area.by_method() {
+                                for encounter in encounters {
+                                    ui.label(method.to_string());
+                                    ui.label(format!("{:03}", encounter.dex_num));
+                                    if ui.selectable_label(false, &encounter.species).clicked() {
+                                        clicked = Some(encounter.species.clone());
+                                    }
+                                    ui.label(encounter.qualifiers.join(", "));
+                                    ui.end_row();
+                                }
+                            }
+                        });
+                    }
+                });
+            }
+        }
+
+        if let Some(species) = clicked {
+            let pokemon_tab = TabContext::with_search(&self.store, species, tab.version.clone());
+            self.opened.push(DexTab::Pokemon(Box::new(pokemon_tab)));
+        }
+        if let Some(area) = clicked_area {
+            let store = match &tab.version {
+                Some(version) => self.store.with_version(version),
+                None => self.store.clone(),
+            };
+            self.opened.push(DexTab::Area(AreaTab::new(store, Some(area), tab.version.clone())));
+        }
+    }
+}
+
+/// Clickable list of the pokemon found, returns the species clicked
+fn pokemon_list_ui(ui: &mut egui::Ui, results: &Promise<Result<Vec<Pokemon>>>) -> Option<String> {
+    let mut clicked = None;
+    match results.ready() {
+        None => {
+            ui.spinner();
+        }
+        Some(Err(err)) => {
+            ui.colored_label(ui.visuals().error_fg_color, format!("{}", err));
+        }
+        Some(Ok(pokemons)) => {
+            ui.label(format!("{} pokemon", pokemons.len()));
+            ScrollArea::vertical().show(ui, |ui| {
+                for pokemon in pokemons {
+                    let text = format!("{:03} {}", pokemon.dex_num, pokemon.species);
+                    if ui.selectable_label(false, text).clicked() {
+                        clicked = Some(pokemon.species.clone());
+                    }
+                }
+            });
+        }
+    }
+    clicked
+}
+
+/// Moves that were previously unavailable to the pokemon are highlighted, the details of the move are
+/// shown on hover and the label can be clicked
+fn move_label(ui: &mut egui::Ui, text: String, is_new: bool, attack: Option<&Move>) -> egui::Response {
+    let mut hover = Vec::new();
+    let text = match is_new {
+        true => {
+            hover.push(String::from("Newly available in Lumi"));
+            RichText::new(text).color(ui.visuals().warn_fg_color)
+        },
+        false => RichText::new(text),
+    };
+    if let Some(details) = attack.and_then(|m| m.details.as_ref()) {
+        hover.push(details.to_string());
+    }
+
+    let response = ui.add(egui::Label::new(text).sense(egui::Sense::click()));
+    match hover.is_empty() {
+        true => response,
+        false => response.on_hover_text(hover.join("\n")),
     }
 }
 
 impl MyApp {
-    fn new(cc: &eframe::CreationContext<'_>) -> Self {
+    fn new(cc: &eframe::CreationContext<'_>, store: Arc<dyn PokedexStore>, versions: Vec<String>, releases: HashMap<String, ReleaseData>) -> Self {
         let mut style = (*cc.egui_ctx.style()).clone();
         style.text_styles = [
             (TextStyle::Heading, FontId::new(30.0, Proportional)),
@@ -251,15 +1335,76 @@ impl MyApp {
         ].into();
         cc.egui_ctx.set_style(style);
 
-        let tree = Tree::new(vec![TabContext::default()]);
+        let tree = Tree::new(vec![DexTab::Pokemon(Box::default())]);
+
+        Self {
+            tree,
+            store,
+            versions,
+            releases,
+            show_filters: false,
+            filter: QueryFilter::default(),
+            filter_results: None,
+        }
+    }
+}
+
+impl MyApp {
+    /// Sidebar to search the pokedex by typing, ability, stats and dex number, clicking a result
+    /// opens it in the focused tab
+    fn filters_ui(&mut self, ui: &mut egui::Ui) {
+        ui.add(query_filter(&mut self.filter));
+        ui.horizontal(|ui| {
+            if ui.button("Search").clicked() {
+                let store = self.store.clone();
+                let query = self.filter.query();
+                self.filter_results = Some(Promise::spawn_async(async move { store.search(&query).await }));
+            }
+            if ui.button("Clear").clicked() {
+                self.filter = QueryFilter::default();
+                self.filter_results = None;
+            }
+        });
+        ui.separator();
 
-        Self { tree }
+        let clicked = self.filter_results.as_ref().and_then(|results| pokemon_list_ui(ui, results));
+        if let Some(species) = clicked {
+            match self.tree.find_active_focused() {
+                Some((_, DexTab::Pokemon(tab))) => {
+                    tab.search_text = species;
+                    tab.search(&self.store);
+                }
+                _ => {
+                    let tab = TabContext::with_search(&self.store, species, None);
+                    self.tree.push_to_focused_leaf(DexTab::Pokemon(Box::new(tab)));
+                }
+            }
+        }
     }
 }
 
 impl eframe::App for MyApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::TopBottomPanel::top("toolbar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.toggle_value(&mut self.show_filters, "Filters");
+                if ui.button("TMs").clicked() {
+                    self.tree.push_to_focused_leaf(DexTab::Tms(TmTab::new(self.store.clone())));
+                }
+                if ui.button("Areas").clicked() {
+                    self.tree.push_to_focused_leaf(DexTab::Area(AreaTab::new(self.store.clone(), None, None)));
+                }
+                if ui.button("Gifts").clicked() {
+                    self.tree.push_to_focused_leaf(DexTab::Gifts(GiftsTab::new(self.store.clone())));
+                }
+            });
+        });
+        egui::SidePanel::left("filters")
+            .resizable(true)
+            .show_animated(ctx, self.show_filters, |ui| self.filters_ui(ui));
+
         let mut added_nodes = Vec::new();
+        let mut opened = Vec::new();
         DockArea::new(&mut self.tree)
             .show_add_buttons(true)
             .draggable_tabs(false)
@@ -272,12 +1417,17 @@ impl eframe::App for MyApp {
                 ctx,
                 &mut TabViewer {
                     added_nodes: &mut added_nodes,
+                    opened: &mut opened,
+                    store: self.store.clone(),
+                    versions: &self.versions,
+                    releases: &self.releases,
                 },
             );
 
         added_nodes.drain(..).for_each(|node| {
             self.tree.set_focused_node(node);
-            self.tree.push_to_focused_leaf(TabContext::default());
+            self.tree.push_to_focused_leaf(DexTab::Pokemon(Box::default()));
         });
+        opened.drain(..).for_each(|tab| self.tree.push_to_focused_leaf(tab));
     }
 }
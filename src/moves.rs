@@ -0,0 +1,230 @@
+/// This library holds the "MoveRegistry", every move learnt in the pokedex with its TM number and its
+/// learners. The dex files only have the move names, the type, category, power, accuracy, PP and
+/// effect come from the optional "moves.json" file next to them
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt, fs, path::Path, str::FromStr};
+use anyhow::Result;
+
+use crate::{normalize, Pokemon, PokemonType};
+
+/// The name of the move data file in the pokedex directory
+pub const MOVE_DATA_FILE: &str = "moves.json";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum MoveCategory {
+    Physical,
+    Special,
+    Status,
+}
+
+impl fmt::Display for MoveCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for MoveCategory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "physical" => Ok(MoveCategory::Physical),
+            "special" => Ok(MoveCategory::Special),
+            "status" => Ok(MoveCategory::Status),
+            _ => Err(format!("unknown move category \"{}\"", s)),
+        }
+    }
+}
+
+/// An entry of the move data file, only the name is needed:
+/// {"name": "Surf", "type": "Water", "category": "Special", "power": 90, "accuracy": 100, "pp": 15}
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct MoveDetails {
+    pub name: String,
+    #[serde(alias = "type", default)]
+    pub move_type: Option<PokemonType>,
+    #[serde(default)]
+    pub category: Option<MoveCategory>,
+    #[serde(default)]
+    pub power: Option<u16>,
+    #[serde(default)]
+    pub accuracy: Option<u16>,
+    #[serde(default)]
+    pub pp: Option<u8>,
+    #[serde(default)]
+    pub effect: Option<String>,
+}
+
+impl fmt::Display for MoveDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = |value: Option<u16>| value.map_or(String::from("-"), |v| v.to_string());
+        let mut fields = Vec::new();
+        if let Some(move_type) = self.move_type {
+            fields.push(move_type.to_string());
+        }
+        if let Some(category) = self.category {
+            fields.push(category.to_string());
+        }
+        fields.push(format!("Power {}", value(self.power)));
+        fields.push(format!("Accuracy {}", value(self.accuracy)));
+        fields.push(format!("PP {}", value(self.pp.map(u16::from))));
+        write!(f, "{}", fields.join(" · "))?;
+        if let Some(effect) = &self.effect {
+            write!(f, "\n{}", effect)?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads the move data file of the pokedex directory, a directory without one has no details
+pub fn load_move_details(data_dir: &Path) -> Result<Vec<MoveDetails>> {
+    let path = data_dir.join(MOVE_DATA_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+/// "tm" is the first TM number found for the move, "learners" are the species in dex order
+#[derive(Debug, Clone, PartialEq)]
+pub struct Move {
+    pub name: String,
+    pub tm: Option<u16>,
+    pub learners: Vec<String>,
+    pub details: Option<MoveDetails>,
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if let Some(tm) = self.tm {
+            write!(f, " (TM{:02})", tm)?;
+        }
+        if let Some(details) = &self.details {
+            write!(f, "\n{}", details)?;
+        }
+        write!(f, "\nLearnt by {} pokemon", self.learners.len())
+    }
+}
+
+/// The moves sorted by name
+#[derive(Debug, Clone, Default)]
+pub struct MoveRegistry {
+    pub moves: Vec<Move>,
+}
+
+impl MoveRegistry {
+    /// The dex spells some moves several ways, "U-Turn" and "U-turn" or "Solarbeam" and "Solar Beam":
+    /// the moves are merged by normalized name and shown with their most common spelling
+    pub fn new(pokedex: &[Pokemon]) -> Self {
+        let mut spellings: HashMap<String, Vec<(String, usize)>> = HashMap::new();
+        let mut moves: HashMap<String, Move> = HashMap::new();
+        for pokemon in pokedex {
+            let learnt = pokemon.lvl_up_moves.iter().map(|m| (&m.name, None))
+                .chain(pokemon.tms.iter().map(|m| (&m.name, Some(m.num))))
+                .chain(pokemon.egg_moves.iter().map(|m| (&m.name, None)));
+            for (name, tm) in learnt {
+                let key = normalize(name);
+                let counts = spellings.entry(key.clone()).or_default();
+                match counts.iter_mut().find(|(spelling, _)| spelling == name) {
+                    Some((_, count)) => *count += 1,
+                    None => counts.push((name.clone(), 1)),
+                }
+                let entry = moves
+                    .entry(key)
+                    .or_insert_with(|| Move { name: name.clone(), tm: None, learners: Vec::new(), details: None });
+                entry.tm = entry.tm.or(tm);
+                if entry.learners.last() != Some(&pokemon.species) {
+                    entry.learners.push(pokemon.species.clone());
+                }
+            }
+        }
+        let mut moves: Vec<Move> = moves
+            .into_iter()
+            .map(|(key, mut entry)| {
+                // the first spelling found wins the ties
+                let counts = &spellings[&key];
+                let most = counts.iter().map(|(_, count)| *count).max().unwrap_or(0);
+                if let Some((spelling, _)) = counts.iter().find(|(_, count)| *count == most) {
+                    entry.name = spelling.clone();
+                }
+                entry
+            })
+            .collect();
+        moves.sort_by(|a, b| a.name.cmp(&b.name));
+        MoveRegistry { moves }
+    }
+
+    /// Adds the details to the moves with the same name, the details of unknown moves are ignored
+    pub fn with_details(mut self, details: Vec<MoveDetails>) -> Self {
+        for details in details {
+            let name = normalize(&details.name);
+            if let Some(entry) = self.moves.iter_mut().find(|m| normalize(&m.name) == name) {
+                entry.details = Some(details);
+            }
+        }
+        self
+    }
+
+    /// Finds the move by name, ignoring case and punctuation
+    pub fn get(&self, name: &str) -> Option<&Move> {
+        let name = normalize(name);
+        self.moves.iter().find(|m| normalize(&m.name) == name)
+    }
+
+    /// The moves of the type and category, the moves without details never match a filter
+    pub fn filter(&self, move_type: Option<PokemonType>, category: Option<MoveCategory>) -> Vec<Move> {
+        self.moves
+            .iter()
+            .filter(|m| move_type.is_none() || m.details.as_ref().is_some_and(|d| d.move_type == move_type))
+            .filter(|m| category.is_none() || m.details.as_ref().is_some_and(|d| d.category == category))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_pokedex, parser::test_fixtures::*};
+
+    #[test]
+    fn registry_with_details() {
+        let (_, pokedex) = parse_pokedex(&bulbasaur_ivysaur()).unwrap();
+        let details: Vec<MoveDetails> = serde_json::from_str(
+            r#"[{"name": "toxic", "type": "Poison", "category": "Status", "accuracy": 90, "pp": 10}, {"name": "Surf"}]"#,
+        )
+        .unwrap();
+        let registry = MoveRegistry::new(&pokedex).with_details(details);
+
+        let names: Vec<&str> = registry.moves.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["Skull Bash", "Tackle", "Toxic"]);
+        let toxic = registry.get("TOXIC").unwrap();
+        assert_eq!(toxic.tm, Some(6));
+        assert_eq!(toxic.learners, ["Bulbasaur", "Ivysaur"]);
+        assert_eq!(toxic.details.as_ref().unwrap().pp, Some(10));
+        assert!(registry.get("Surf").is_none());
+
+        assert_eq!(registry.filter(Some(PokemonType::Poison), None).len(), 1);
+        assert_eq!(registry.filter(None, Some(MoveCategory::Status))[0].name, "Toxic");
+        assert!(registry.filter(None, Some(MoveCategory::Physical)).is_empty());
+        assert_eq!(registry.filter(None, None).len(), 3);
+    }
+
+    /// The moves spelled several ways in the dex files are a single entry of the registry
+    #[test]
+    fn registry_of_the_dex_files() {
+        let registry = MoveRegistry::new(&dex_files());
+
+        let mut names: Vec<String> = registry.moves.iter().map(|m| normalize(&m.name)).collect();
+        names.sort();
+        let count = names.len();
+        names.dedup();
+        assert_eq!(names.len(), count);
+
+        let u_turn = registry.get("u-turn").unwrap();
+        assert_eq!(u_turn.name, "U-turn");
+        assert!(u_turn.learners.contains(&String::from("Paras")));
+        assert_eq!(registry.get("Solarbeam").unwrap().name, registry.get("Solar Beam").unwrap().name);
+    }
+}
@@ -4,26 +4,242 @@ use nom::bytes::complete::{take, take_until, take_while1};
 use nom::character::complete::{char, digit1};
 use nom::combinator::{map_res, rest};
 use nom::sequence::separated_pair;
+use nom::error::{Error, ErrorKind};
 use nom::{branch::alt,IResult};
-use anyhow::{anyhow, Result};
 use std::str::FromStr;
+use std::{fmt, fs, path::Path};
 
 use crate::pokemon::*;
+use crate::type_chart::PokemonType;
+use crate::config::DEFAULT_VERSION;
 
-pub fn parse_pokedex(input: &str) -> Result<(&str, Vec<Pokemon>)> {
+pub fn parse_pokedex(input: &str) -> Result<(&str, Vec<Pokemon>), ParseError> {
+    let source = input;
     let mut input = input;
     let mut pokedex = vec![];
     loop {
         if input.is_empty() {
             break;
         }
-        let (output, result) = pokemon(input).map_err(|e| anyhow!("{}", e))?;
+        let (output, result) = pokemon_entry(input)
+            .map_err(|(section, e)| ParseError::new(source, input, section, e))?;
         pokedex.push(result);
         input = output;
     }
     Ok((input, pokedex))
 }
 
+/// Lenient version of "parse_pokedex", an entry that fails to parse is skipped and the parser
+/// resynchronizes on the next "NNN-Species" header. Returns the parsed entries and the errors of
+/// the skipped ones
+pub fn parse_pokedex_lenient(input: &str) -> (Vec<Pokemon>, Vec<ParseError>) {
+    let mut pokedex = vec![];
+    let mut errors = vec![];
+    for entry in entries(input) {
+        match pokemon_entry(entry) {
+            Ok((_, result)) => pokedex.push(result),
+            Err((section, e)) => errors.push(ParseError::new(input, entry, section, e)),
+        }
+    }
+    (pokedex, errors)
+}
+
+/// Parses every ".txt" file of the data directory in lenient mode, the files are read sorted by name
+/// so the dex keeps its order. Returns the parsed pokemon and the errors of the entries that had to be
+/// skipped
+pub fn load_pokedex(data_dir: &Path) -> anyhow::Result<(Vec<Pokemon>, Vec<ParseError>)> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(data_dir)
+        .map_err(|e| anyhow::anyhow!("could not read the pokedex directory {}: {}", data_dir.display(), e))?
+    {
+        let path = entry?.path();
+        if path.extension().is_some_and(|extension| extension == "txt") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    if files.is_empty() {
+        anyhow::bail!("no pokedex files found in {}", data_dir.display());
+    }
+
+    let mut pokedex = Vec::new();
+    let mut skipped = Vec::new();
+
+    for file in files {
+        let pokedex_string = fs::read_to_string(&file)?;
+        let (mut parsed_pokedex, errors) = parse_pokedex_lenient(&pokedex_string);
+        pokedex.append(&mut parsed_pokedex);
+        let file = file.display().to_string();
+        skipped.extend(errors.into_iter().map(|e| e.with_file(&file)));
+    }
+
+    Ok((pokedex, skipped))
+}
+
+/// Splits the file into one slice per pokedex entry, each starting at its "NNN-Species" line.
+/// Text before the first entry (the notation notes) is dropped
+pub(crate) fn entries(input: &str) -> Vec<&str> {
+    let mut starts = vec![];
+    let mut offset = 0;
+    for line in input.split_inclusive('\n') {
+        if is_header(line) {
+            starts.push(offset);
+        }
+        offset += line.len();
+    }
+
+    if starts.is_empty() {
+        return match input.trim().is_empty() {
+            true => vec![],
+            false => vec![input],
+        };
+    }
+
+    let mut ends = starts[1..].to_vec();
+    ends.push(input.len());
+    starts
+        .iter()
+        .zip(ends)
+        .map(|(&start, end)| &input[start..end])
+        .collect()
+}
+
+/// Checks if the line is an entry header, three digits a dash and the species: "001- Bulbasaur"
+fn is_header(line: &str) -> bool {
+    let line = line.trim_start_matches('\u{feff}');
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    digits == 3
+        && line[3..].starts_with('-')
+        && line[4..].trim_start().starts_with(char::is_alphabetic)
+}
+
+/// The sections of a pokedex entry, used to tell where in an entry the parser failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Section {
+    Header,
+    Stats,
+    Type,
+    Abilities,
+    HeldItems,
+    Location,
+    LevelUp,
+    TMs,
+    EggMoves,
+}
+
+impl fmt::Display for Section {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let section = match self {
+            Section::Header => "Header",
+            Section::Stats => "Stats",
+            Section::Type => "Type",
+            Section::Abilities => "Abilities",
+            Section::HeldItems => "Wild Held Items",
+            Section::Location => "Location",
+            Section::LevelUp => "Level Up",
+            Section::TMs => "TMs",
+            Section::EggMoves => "Egg Moves",
+        };
+        write!(f, "{}", section)
+    }
+}
+
+/// Error returned by "parse_pokedex", it points to the line of the dex file that could not be
+/// parsed and the pokedex entry it belongs to so the source text can be fixed
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    pub file: Option<String>,
+    pub line: usize,
+    pub column: usize,
+    pub dex_num: Option<u32>,
+    pub species: Option<String>,
+    pub section: Section,
+    pub kind: ErrorKind,
+    pub snippet: String,
+}
+
+impl ParseError {
+    /// Builds the error from the whole file ("source"), the start of the entry that failed and
+    /// the nom error, every slice handled by the parsers points inside "source"
+    pub fn new(source: &str, entry: &str, section: Section, error: nom::Err<Error<&str>>) -> Self {
+        let (at, kind) = match error {
+            nom::Err::Error(e) | nom::Err::Failure(e) => (e.input, e.code),
+            nom::Err::Incomplete(_) => (entry, ErrorKind::Eof),
+        };
+        let offset = offset_in(source, at)
+            .or_else(|| offset_in(source, entry))
+            .unwrap_or(0);
+
+        let before = &source[..offset];
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let line = before.matches('\n').count() + 1;
+        let column = before[line_start..].chars().count() + 1;
+        let snippet = source[line_start..]
+            .lines()
+            .next()
+            .unwrap_or("")
+            .trim()
+            .chars()
+            .take(80)
+            .collect();
+
+        let (dex_num, species) = match header(entry) {
+            Some((dex_num, species)) => (Some(dex_num), Some(species)),
+            None => (None, None),
+        };
+
+        ParseError {
+            file: None,
+            line,
+            column,
+            dex_num,
+            species,
+            section,
+            kind,
+            snippet,
+        }
+    }
+
+    pub fn with_file(mut self, file: &str) -> Self {
+        self.file = Some(file.to_string());
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file)?;
+        }
+        write!(f, "{}:{}: could not parse {}", self.line, self.column, self.section)?;
+        match (&self.dex_num, &self.species) {
+            (Some(dex_num), Some(species)) => write!(f, " of {:03}-{}", dex_num, species)?,
+            _ => write!(f, " of unknown entry")?,
+        }
+        write!(f, " ({})\n    {}", self.kind.description(), self.snippet)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Byte offset of "slice" inside "source", None when it isn't a subslice of it
+fn offset_in(source: &str, slice: &str) -> Option<usize> {
+    let start = source.as_ptr() as usize;
+    let ptr = slice.as_ptr() as usize;
+    if ptr >= start && ptr <= start + source.len() {
+        Some(ptr - start)
+    } else {
+        None
+    }
+}
+
+/// Reads the "NNN-Species" line of an entry without parsing the rest of it
+fn header(entry: &str) -> Option<(u32, String)> {
+    let (input, dex_num) = dex_num(entry).ok()?;
+    let (_, species) = species(input).ok()?;
+    Some((dex_num, species.trim().to_string()))
+}
+
 /// The pokedex entry has the following format:
 /// 001- Bulbasaur
 /// Stats: 45 HP/49 Atk/49 Def/65 SpA/65 SpD/45 Spe/318 BST
@@ -64,23 +280,37 @@ pub fn parse_pokedex(input: &str) -> Result<(&str, Vec<Pokemon>)> {
 /// TM01: Focus Punch
 /// Egg Moves:
 pub fn pokemon(input: &str) -> IResult<&str, Pokemon> {
-    let (input, dex_num) = dex_num(input)?;
-    let (input, species) = species(input)?;
-    let (input, (old_stats, new_stats)) = stats(input)?;
-    let (input, (old_type, new_type)) = p_type(input)?;
-    let (input, abilities) = ability(input)?;
-    let (input, locations) = location(input)?;
-    let (input, moves) = moves(input)?;
-    let (input, tm_moves) = tm_moves(input)?;
-    let (input, egg_moves) = egg_moves(input)?;
+    pokemon_entry(input).map_err(|(_, e)| e)
+}
+
+/// Result of "pokemon_entry", the error carries the section of the entry that failed
+pub type EntryResult<'a> = Result<(&'a str, Pokemon), (Section, nom::Err<Error<&'a str>>)>;
+
+/// Same as "pokemon" but also tells which section of the entry failed to parse
+pub fn pokemon_entry(input: &str) -> EntryResult<'_> {
+    let (input, dex_num) = dex_num(input).map_err(|e| (Section::Header, e))?;
+    let (input, species) = species(input).map_err(|e| (Section::Header, e))?;
+    let (input, (old_stats, new_stats)) = stats(input).map_err(|e| (Section::Stats, e))?;
+    let (input, (old_type, new_type)) = p_type(input).map_err(|e| (Section::Type, e))?;
+    let (input, abilities) = ability(input).map_err(|e| (Section::Abilities, e))?;
+    let (input, held_items) = held_items(input).map_err(|e| (Section::HeldItems, e))?;
+    let (input, locations) = location(input).map_err(|e| (Section::Location, e))?;
+    let (input, moves) = moves(input).map_err(|e| (Section::LevelUp, e))?;
+    let (input, tm_moves) = tm_moves(input).map_err(|e| (Section::TMs, e))?;
+    let (input, egg_moves) = egg_moves(input).map_err(|e| (Section::EggMoves, e))?;
+    let (base_species, form) = species_form(&species);
     let pokemon = Pokemon {
+        version: DEFAULT_VERSION.to_string(),
         dex_num: dex_num,
-        species: species.trim().to_string(),
+        species: species_name(&base_species, form),
+        base_species: base_species,
+        form: form,
         pokemon_type: old_type,
         new_pokemon_type: new_type,
         stats: old_stats,
         new_stats: new_stats,
         abilities: abilities,
+        held_items: held_items,
         locations: locations,
         lvl_up_moves: moves,
         tms: tm_moves,
@@ -90,30 +320,159 @@ pub fn pokemon(input: &str) -> IResult<&str, Pokemon> {
     Ok((input, pokemon))
 }
 
-pub fn location(input: &str) -> IResult<&str, Vec<String>> {
+/// The input up to the first line starting with "header". The section headers start their line, the
+/// locations mentioning them don't: "* Evolve Riolu (Level Up with Aura Sphere learned)"
+fn take_until_line<'a>(header: &'static str) -> impl Fn(&'a str) -> IResult<&'a str, &'a str> {
+    move |input: &'a str| {
+        let mut offset = 0;
+        for line in input.split_inclusive('\n') {
+            if line.trim_start().starts_with(header) {
+                return Ok((&input[offset..], &input[..offset]));
+            }
+            offset += line.len();
+        }
+        Err(nom::Err::Error(Error::new(input, ErrorKind::TakeUntil)))
+    }
+}
+
+pub fn location(input: &str) -> IResult<&str, Vec<Encounter>> {
     let (input, _) = take_until("Location")(input)?;
-    let (input, result) = take_until("Level Up")(input)?;
+    let (input, result) = take_until_line("Level Up")(input)?;
 
+    // Long locations are wrapped into several lines, the lines that don't start with "*" are
+    // joined to the previous location while its parentheses are still open
     let locations = result.replace("Location:", "");
-    let locations: Vec<String> = locations
-        .lines()
-        .filter_map(|s| {
-            if !s.is_empty() {
-                Some(s.replace("*", "").trim().to_string())
-            } else {
-                None
+    let mut lines: Vec<String> = Vec::new();
+    for line in locations.lines().map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        match lines.last_mut() {
+            Some(last) if !line.starts_with('*') && last.matches('(').count() > last.matches(')').count() => {
+                last.push(' ');
+                last.push_str(line);
             }
-        })
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let locations: Vec<Encounter> = lines
+        .iter()
+        .map(|s| encounter(s.replace("*", "").trim()))
         .collect();
 
     Ok((input, locations))
 }
 
+/// Parses a single location: "Route 205 (North, Honey Tree, 20% chance)"
+pub fn encounter(input: &str) -> Encounter {
+    let (area, details) = match (input.find('('), input.rfind(')')) {
+        (Some(start), Some(end)) if start < end => (&input[..start], &input[start + 1..end]),
+        (Some(start), None) => (&input[..start], &input[start + 1..]),
+        _ => (input, ""),
+    };
+    let area = area.trim();
+
+    // Evolutions only list their condition inside the parentheses: "Evolve Ivysaur (Level 32)"
+    if let Some(species) = area.strip_prefix("Evolve ") {
+        let (base_species, form) = species_form(species);
+        return Encounter {
+            area: species_name(&base_species, form),
+            method: EncounterMethod::Evolution,
+            rate: None,
+            qualifiers: details
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect(),
+        };
+    }
+
+    let mut rate = None;
+    let mut parts = Vec::new();
+    for part in details.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        match encounter_rate(part) {
+            Some(chance) => rate = Some(chance),
+            None => parts.push((part, encounter_method(part))),
+        }
+    }
+
+    // Several methods can be listed for the same location ("Surfing, Incense" or "Old Rod,
+    // Good Rod"), the most specific one is kept and the others are left as qualifiers
+    let methods: Vec<EncounterMethod> = parts
+        .iter()
+        .filter_map(|(_, found)| found.as_ref().map(|(method, _)| *method))
+        .collect();
+    let rods = methods.iter().filter(|method| method.is_rod()).count();
+    let method = match methods.iter().max() {
+        Some(method) if method.is_rod() && rods > 1 => EncounterMethod::Fishing,
+        Some(method) => *method,
+        None if rate.is_some() => EncounterMethod::Grass,
+        None => EncounterMethod::Other,
+    };
+
+    let mut qualifiers: Vec<String> = Vec::new();
+    for (part, found) in parts {
+        let qualifier = match found {
+            Some((found, rest)) if found == method => rest,
+            _ => part.to_string(),
+        };
+        if !qualifier.is_empty() && !qualifiers.contains(&qualifier) {
+            qualifiers.push(qualifier);
+        }
+    }
+
+    Encounter {
+        area: area.to_string(),
+        method,
+        rate,
+        qualifiers,
+    }
+}
+
+/// Reads the encounter rate: "20% chance"
+fn encounter_rate(input: &str) -> Option<u8> {
+    let lowercase = input.to_ascii_lowercase();
+    let chance = lowercase.strip_suffix("chance").unwrap_or(&lowercase).trim();
+    chance.strip_suffix('%')?.trim().parse().ok()
+}
+
+/// Looks for a known encounter method in part of a location, returns the method and what is left
+/// of the text once the method is removed: "South Honey Tree" => (HoneyTree, "South")
+fn encounter_method(input: &str) -> Option<(EncounterMethod, String)> {
+    let keywords = [
+        ("Static Encounter", EncounterMethod::Static),
+        ("Baby Egg Gift", EncounterMethod::EggGift),
+        ("Gift Egg", EncounterMethod::EggGift),
+        ("Gift", EncounterMethod::Gift),
+        ("Fossil Restoration", EncounterMethod::Fossil),
+        ("Trade", EncounterMethod::Trade),
+        ("Honey Tree", EncounterMethod::HoneyTree),
+        ("Incense", EncounterMethod::Incense),
+        ("Poke Radar", EncounterMethod::PokeRadar),
+        ("Poké Radar", EncounterMethod::PokeRadar),
+        ("Old Rod", EncounterMethod::OldRod),
+        ("Good Rod", EncounterMethod::GoodRod),
+        ("Super Rod", EncounterMethod::SuperRod),
+        ("Any Rod", EncounterMethod::Fishing),
+        ("All Rods", EncounterMethod::Fishing),
+        ("Fishing", EncounterMethod::Fishing),
+        ("Surfing", EncounterMethod::Surf),
+        ("Surf", EncounterMethod::Surf),
+    ];
+    let lowercase = input.to_ascii_lowercase();
+    keywords.iter().find_map(|(keyword, method)| {
+        let start = lowercase.find(&keyword.to_ascii_lowercase())?;
+        let rest = format!("{} {}", &input[..start], &input[start + keyword.len()..]);
+        Some((*method, rest.trim().to_string()))
+    })
+}
+
 pub fn dex_num(input: &str) -> IResult<&str, u32> {
     let mut input = input;
     let mut result = "";
     loop {
-        let ch = input.chars().next().unwrap();
+        let ch = match input.chars().next() {
+            Some(ch) => ch,
+            None => return Err(nom::Err::Error(Error::new(input, ErrorKind::Eof))),
+        };
         if ch == '-' {
             break;
         }
@@ -136,6 +495,52 @@ pub fn species(input: &str) -> IResult<&str, String> {
     Ok((input, result.to_string()))
 }
 
+/// Splits a species name into the base species and its form. Accepts the dex notation
+/// ("Rapidash-G", "Wormadam Plant Cloak") and the form name before or after the species
+/// ("Galarian Rapidash", "Rapidash Galarian"), so it's also used to resolve searches
+pub fn species_form(name: &str) -> (String, Option<Form>) {
+    let name = name.trim();
+    let lowercase = name.to_lowercase();
+    for form in Form::ALL.iter().filter(|form| form.is_regional()) {
+        for alias in form.aliases().iter().filter(|alias| alias.starts_with('-')) {
+            if lowercase.ends_with(alias) {
+                let base = &name[..name.len() - alias.len()];
+                return (base.trim().to_string(), Some(*form));
+            }
+        }
+    }
+
+    let words: Vec<&str> = name
+        .split_whitespace()
+        .filter(|word| !["form", "cloak"].contains(&word.to_lowercase().as_str()))
+        .collect();
+    let base_of = |skip: usize| {
+        words
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != skip)
+            .map(|(_, word)| *word)
+            .collect::<Vec<&str>>()
+            .join(" ")
+    };
+
+    for (i, word) in words.iter().enumerate() {
+        let word = word.to_lowercase();
+        for form in Form::ALL {
+            if !form.aliases().contains(&word.as_str()) {
+                continue;
+            }
+            let base = base_of(i);
+            match form.species() {
+                Some(species) if !species.eq_ignore_ascii_case(&base) => continue,
+                _ => return (base, Some(form)),
+            }
+        }
+    }
+
+    (words.join(" "), None)
+}
+
 pub fn hp(input: &str) -> IResult<&str, (u16, Option<u16>)> {
     let (input, result) = take_until("/")(input)?;
     let (input, _) = take(1usize)(input)?;
@@ -329,6 +734,11 @@ pub fn stats(input: &str) -> IResult<&str, (Stats, Option<Stats>)> {
 pub fn p_type(input: &str) -> IResult<&str, (PokemonTyping, Option<PokemonTyping>)> {
     let (input, _) = take_until("Type")(input)?;
     let (input, result) = take_until("Abilities")(input)?;
+    // some entries add a note to the type: "Normal (Fire for Sunny Form, ...)", the note is skipped
+    let result = match result.find('(') {
+        Some(i) => &result[..i],
+        None => result,
+    };
 
     let output = match result.contains(">") {
         true => {
@@ -353,11 +763,11 @@ pub fn p_type(input: &str) -> IResult<&str, (PokemonTyping, Option<PokemonTyping
 pub fn typing(input: &str) -> IResult<&str, PokemonTyping> {
     let (type1, type0) = alt((take_until("/"), rest))(input.trim())?;
     let (type1, _) = alt((take(1usize), rest))(type1)?;
-    let type1 = match type1 {
+    let type1 = match type1.trim() {
         "" => None,
-        s => Some(s.to_string()),
+        _ => Some(single_type(type1)?.1),
     };
-    let type0 = type0.replace("Type:", "").trim().to_string();
+    let (_, type0) = single_type(type0)?;
 
     let result = PokemonTyping {
         type0: type0,
@@ -367,7 +777,14 @@ pub fn typing(input: &str) -> IResult<&str, PokemonTyping> {
     Ok((input, result))
 }
 
-pub fn ability(input: &str) -> IResult<&str, Vec<String>> {
+/// Parses a single type, the "Type:" label is optional: "Type: Grass" => Grass
+pub fn single_type(input: &str) -> IResult<&str, PokemonType> {
+    map_res(rest, |s: &str| s.replace("Type:", "").parse::<PokemonType>())(input)
+}
+
+/// Shellos and Gastrodon list the abilities of the West Side and of the East Side, only the first
+/// line is kept
+pub fn ability(input: &str) -> IResult<&str, Abilities> {
     let (input, _) = take_until("Abilities")(input)?;
     let (_, contains) = contains_item(input)?;
     let (input, result) = match contains {
@@ -375,25 +792,50 @@ pub fn ability(input: &str) -> IResult<&str, Vec<String>> {
         false => take_until("Location")(input)?,
     };
 
-    let abilities: Vec<String> = result
-        .replace("Abilities:", "")
+    let line = result.lines().next().unwrap_or_default();
+    let abilities: Vec<String> = line[line.find(':').map_or(0, |i| i + 1)..]
         .trim()
         .split("/")
-        .map(|s| s.to_string())
+        .map(|s| s.trim().to_string())
         .collect();
 
-    Ok((input, abilities))
+    Ok((input, abilities.into()))
 }
 
 /// This function is used to check if the pokemon has a "Wild Held Items" field
-/// It was abstracted to it's own function in the case that "Wild Held Items" is added to the
-/// pokemon struct
 pub fn contains_item(input: &str) -> IResult<&str, bool> {
     let (_, result) = take_until("Location")(input)?;
     let result = result.contains("Wild Held Items");
     Ok((input, result))
 }
 
+/// The "Wild Held Items" field is optional, when present it has the following format:
+/// Wild Held Items: Big Mushroom (5%), Tiny Mushroom (50%)
+pub fn held_items(input: &str) -> IResult<&str, Vec<HeldItem>> {
+    let (_, contains) = contains_item(input)?;
+    if !contains {
+        return Ok((input, vec![]));
+    }
+
+    let (input, _) = take_until("Wild Held Items")(input)?;
+    let (input, result) = take_until("Location")(input)?;
+
+    let items: Vec<HeldItem> = result
+        .replace("Wild Held Items:", "")
+        .split(',')
+        .filter_map(|s| {
+            let (name, chance) = s.split_once('(')?;
+            let (chance, _) = chance.split_once('%')?;
+            Some(HeldItem {
+                name: name.trim().to_string(),
+                chance: chance.trim().parse().ok()?,
+            })
+        })
+        .collect();
+
+    Ok((input, items))
+}
+
 pub fn moves(input: &str) -> IResult<&str, Vec<LlvUpMoves>> {
     let (input, _) = take_until("Level Up")(input)?;
     let (input, result) = take_until("TMs")(input)?;
@@ -404,9 +846,11 @@ pub fn moves(input: &str) -> IResult<&str, Vec<LlvUpMoves>> {
         .filter_map(|s| {
             if !s.is_empty() {
                 let s: Vec<String> = s.split(":").map(|s| s.to_string()).collect();
+                let (name, is_new) = move_name(s.get(1)?);
                 Some(LlvUpMoves {
                     lvl: s[0].trim().parse().ok()?,
-                    name: s[1].trim().to_string(),
+                    name,
+                    is_new,
                 })
             } else {
                 None
@@ -427,9 +871,11 @@ pub fn tm_moves(input: &str) -> IResult<&str, Vec<TM>> {
         .filter_map(|s| {
             if !s.is_empty() {
                 let s: Vec<String> = s.split(":").map(|s| s.to_string()).collect();
+                let (name, is_new) = move_name(s.get(1)?);
                 Some(TM {
                     num: s[0].replace("TM", "").parse().ok()?,
-                    name: s[1].trim().to_string(),
+                    name,
+                    is_new,
                 })
             } else {
                 None
@@ -440,16 +886,20 @@ pub fn tm_moves(input: &str) -> IResult<&str, Vec<TM>> {
     Ok((input, moves))
 }
 
-pub fn egg_moves(input: &str) -> IResult<&str, Vec<String>> {
+pub fn egg_moves(input: &str) -> IResult<&str, Vec<EggMove>> {
     let (input, _) = take_until("Egg Moves")(input)?;
     let (input, result) = take_while1(|c: char| !c.is_ascii_digit())(input)?;
 
     let moves = result.replace("Egg Moves:", "");
-    let moves: Vec<String> = moves
+    let moves: Vec<EggMove> = moves
         .lines()
         .filter_map(|s| {
             if !s.is_empty() {
-                Some(s.to_string())
+                let (name, is_new) = move_name(s);
+                Some(EggMove {
+                    name,
+                    is_new,
+                })
             } else {
                 None
             }
@@ -459,6 +909,38 @@ pub fn egg_moves(input: &str) -> IResult<&str, Vec<String>> {
     Ok((input, moves))
 }
 
+/// Splits the "(!!)" marker, used for moves previously unavailable to the pokemon, from the move
+/// name: "Earth Power (!!)" => ("Earth Power", true)
+pub fn move_name(input: &str) -> (String, bool) {
+    let is_new = input.contains("(!!)");
+    (input.replace("(!!)", "").trim().to_string(), is_new)
+}
+
+/// The entries and dex files shared by the tests of the library
+#[cfg(test)]
+pub(crate) mod test_fixtures {
+    use std::path::Path;
+
+    use crate::{load_pokedex, Pokemon};
+
+    /// A gift with a level up move, a TM and an egg move
+    pub const BULBASAUR: &str = "001- Bulbasaur\nStats: 45 HP/49 Atk/49 Def/65 SpA/65 SpD/45 Spe/318 BST\nType: Grass/Poison\nAbilities: Chlorophyll/Overgrow/Grassy Surge\nLocation:\n* Jubilife City Pokémon Center (Gift)\nLevel Up:\n1: Tackle\nTMs:\nTM06: Toxic\nEgg Moves:\nSkull Bash\n";
+
+    /// The evolution of Bulbasaur with the same moves
+    pub const IVYSAUR: &str = "002- Ivysaur\nStats: 60 HP/62 Atk/63 Def/80 SpA/80 SpD/60 Spe/405 BST\nType: Grass/Poison\nAbilities: Chlorophyll/Overgrow/Grassy Surge\nLocation:\n* Evolve Bulbasaur (Level 16)\nLevel Up:\n1: Tackle\nTMs:\nTM06: Toxic\nEgg Moves:\nSkull Bash\n";
+
+    /// Both entries as they're written in a dex file
+    pub fn bulbasaur_ivysaur() -> String {
+        format!("{}\n{}", BULBASAUR, IVYSAUR)
+    }
+
+    /// Every pokemon of the dex files of the repository
+    pub fn dex_files() -> Vec<Pokemon> {
+        let (pokedex, _) = load_pokedex(&Path::new(env!("CARGO_MANIFEST_DIR")).join("pokedex")).unwrap();
+        pokedex
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -476,4 +958,77 @@ mod tests {
         let result = 2 + 2;
         assert_eq!(result, 4);
     }
+
+    #[test]
+    fn parse_error_location() {
+        let example = "001- Bulbasaur\nStats: 45 HP/49 Atk/49 Def/65 SpA/65 SpD/45 Spe/318 BST\nType: Grass/Poison\nAbilities: Chlorophyll/Overgrow/Grassy Surge\nLocation:\n* Jubilife City\n\n\nTMs:\nTM06: Toxic\n";
+        let error = parse_pokedex(example).unwrap_err().with_file("kanto.txt");
+        assert_eq!(error.file.as_deref(), Some("kanto.txt"));
+        assert_eq!(error.section, Section::Location);
+        assert_eq!(error.dex_num, Some(1));
+        assert_eq!(error.species.as_deref(), Some("Bulbasaur"));
+        assert_eq!(error.line, 5);
+        assert_eq!(error.snippet, "Location:");
+    }
+
+    #[test]
+    fn form_aliases() {
+        let galarian = (String::from("Rapidash"), Some(Form::Galarian));
+        assert_eq!(species_form("Rapidash-G"), galarian);
+        assert_eq!(species_form("galarian rapidash"), (String::from("rapidash"), Some(Form::Galarian)));
+        assert_eq!(species_form("Rapidash Galarian"), galarian);
+        assert_eq!(species_form("Wormadam Plant Cloak"), (String::from("Wormadam"), Some(Form::PlantCloak)));
+        assert_eq!(species_form("Mr. Mime-G"), (String::from("Mr. Mime"), Some(Form::Galarian)));
+        assert_eq!(species_form("Heatran"), (String::from("Heatran"), None));
+        assert_eq!(species_name("Rotom", species_form("Rotom Heat").1), "Rotom Heat");
+    }
+
+    #[test]
+    fn encounter_parsing() {
+        let honey_tree = encounter("Route 205 (South Honey Tree, 20% chance)");
+        assert_eq!(honey_tree.area, "Route 205");
+        assert_eq!(honey_tree.method, EncounterMethod::HoneyTree);
+        assert_eq!(honey_tree.rate, Some(20));
+        assert_eq!(honey_tree.qualifiers, ["South"]);
+
+        let fishing = encounter("Lake Verity (Old Rod, Good Rod, 30% chance)");
+        assert_eq!(fishing.method, EncounterMethod::Fishing);
+        assert_eq!(fishing.qualifiers, ["Old Rod", "Good Rod"]);
+
+        let evolution = encounter("Evolve Charmeleon (Level 36)");
+        assert_eq!(evolution.area, "Charmeleon");
+        assert_eq!(evolution.method, EncounterMethod::Evolution);
+        assert_eq!(evolution.qualifiers, ["Level 36"]);
+        assert_eq!(format!("{}", evolution), "Evolve Charmeleon (Level 36)");
+    }
+
+    /// The evolutions leveling up under a condition don't end the Location section
+    #[test]
+    fn location_mentioning_level_up() {
+        let example = "122-Mr. Mime\nStats: 40>50 HP/45>35 Atk/65 Def/100>110 SpA/120 SpD/90>95 Spe/460>475 BST\nType: Psychic/Fairy\nAbilities: Filter/Technician/Misty Surge\nLocation:\n      * Evolve Mime Jr. (Level Up with Mimic learnt)\n      * Route 218 (10% chance)\n      * Route 222 (10% chance)\n\nLevel Up:\n1: Barrier\nTMs:\nTM04: Calm Mind\nEgg Moves:\n";
+        let (_, pokedex) = parse_pokedex(example).unwrap();
+        let mr_mime = &pokedex[0];
+
+        let evolution = &mr_mime.locations[0];
+        assert_eq!((evolution.area.as_str(), evolution.method), ("Mime Jr.", EncounterMethod::Evolution));
+        assert_eq!(evolution.qualifiers, ["Level Up with Mimic learnt"]);
+        let areas: Vec<&str> = mr_mime.locations[1..].iter().map(|l| l.area.as_str()).collect();
+        assert_eq!(areas, ["Route 218", "Route 222"]);
+        assert_eq!(mr_mime.lvl_up_moves[0].name, "Barrier");
+
+        let pokedex = test_fixtures::dex_files();
+        let mr_mime = pokedex.iter().find(|p| p.species == "Mr. Mime").unwrap();
+        assert_eq!(mr_mime.locations.len(), 3);
+    }
+
+    #[test]
+    fn lenient_skips_broken_entry() {
+        let example = "Notation\n\n001- Bulbasaur\nStats: 45 HP/49 Atk/49 Def/65 SpA/65 SpD/45 Spe/318 BST\nType: Grass/Poison\nAbilities: Chlorophyll/Overgrow/Grassy Surge\nLocation:\n* Jubilife City Pokémon Center (Gift)\nLevel Up:\n1: Tackle\nTMs:\nTM06: Toxic\nEgg Moves:\nSkull Bash\n\n002- Ivysaur\nStats: 60 HP/62 Atk/63 Def/80 SpA/80 SpD/60 Spe/405 BST\nAbilities: Chlorophyll/Overgrow/Grassy Surge\n\n003- Venusaur\nStats: 80 HP/82 Atk/83 Def/100 SpA/100 SpD/80 Spe/525 BST\nType: Grass/Poison\nAbilities: Chlorophyll/Thick Fat/Grassy Surge\nLocation:\n* Evolve Ivysaur (Level 32)\nLevel Up:\n1: Tackle\nTMs:\nTM06: Toxic\nEgg Moves:\nSkull Bash\n";
+        let (pokedex, errors) = parse_pokedex_lenient(example);
+        let species: Vec<&str> = pokedex.iter().map(|p| p.species.as_str()).collect();
+        assert_eq!(species, ["Bulbasaur", "Venusaur"]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].species.as_deref(), Some("Ivysaur"));
+        assert_eq!(errors[0].section, Section::Stats);
+    }
 }
@@ -3,19 +3,37 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+use crate::type_chart::{DefensiveProfile, PokemonType};
+use crate::config::DEFAULT_VERSION;
+use crate::stat_change::StatChanges;
+
+fn default_version() -> String {
+    DEFAULT_VERSION.to_string()
+}
+
+/// "species" is the name shown to the user, it's built from the base species and the form:
+/// "Rapidash Galarian", "Wormadam Plant". "version" is the Lumi release the entry belongs to
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Pokemon {
+    #[serde(default = "default_version")]
+    pub version: String,
     pub dex_num: u32,
     pub species: String,
+    #[serde(default)]
+    pub base_species: String,
+    #[serde(default)]
+    pub form: Option<Form>,
     pub pokemon_type: PokemonTyping,
     pub new_pokemon_type: Option<PokemonTyping>,
     pub stats: Stats,
     pub new_stats: Option<Stats>,
-    pub abilities: Vec<String>,
-    pub locations: Vec<String>,
+    pub abilities: Abilities,
+    #[serde(default)]
+    pub held_items: Vec<HeldItem>,
+    pub locations: Vec<Encounter>,
     pub lvl_up_moves: Vec<LlvUpMoves>,
     pub tms: Vec<TM>,
-    pub egg_moves: Vec<String>,
+    pub egg_moves: Vec<EggMove>,
 }
 
 impl Pokemon {
@@ -50,6 +68,34 @@ impl Pokemon {
             None => self.stats.clone(),
         }
     }
+
+    /// Key of the pokemon that doesn't change between imports of a release, the version, the dex
+    /// number and the form: "2.0F_026", "2.0F_026_alolan", "2.0F_413_plant"
+    pub fn record_id(&self) -> String {
+        match self.form {
+            Some(form) => format!("{}_{:03}_{}", self.version, self.dex_num, form.name().to_lowercase()),
+            None => format!("{}_{:03}", self.version, self.dex_num),
+        }
+    }
+
+    /// The typing used in game, the changed typing when there is one
+    pub fn get_typing(&self) -> &PokemonTyping {
+        self.new_pokemon_type.as_ref().unwrap_or(&self.pokemon_type)
+    }
+
+    /// The matchups of the typing, then the ones of every ability changing them: Levitate Gengar
+    /// isn't weak to Ground
+    pub fn defensive_profiles(&self) -> Vec<(Option<String>, DefensiveProfile)> {
+        let typing = self.get_typing().defensive_profile();
+        let mut profiles = vec![(None, typing.clone())];
+        for ability in self.abilities.names() {
+            let profile = self.get_typing().defensive_profile_with(&ability);
+            if profile != typing && !profiles.iter().any(|(name, _)| name.as_ref() == Some(&ability)) {
+                profiles.push((Some(ability), profile));
+            }
+        }
+        profiles
+    }
 }
 
 impl fmt::Display for Pokemon {
@@ -64,104 +110,35 @@ impl fmt::Display for Pokemon {
             display.push(format!(" => {}", new_pokemon_type));
         }
 
-        display.push(format!("\nAbilities: {}", self.abilities.join("/")));
-        display.push(format!("\nLocations: \n{}", self.locations.join("\n")));
-
-        // this section checks for new base stats (base stats changed for Pokemon Luminescent Platinum)
-        if let Some(new_stats) = &self.new_stats {
-            let mut stats = Vec::new();
-            let mut old_bst = Vec::new();
-            let mut new_bst = Vec::new();
-
-            match new_stats.hp {
-                Some(hp) => {
-                    stats.push(format!("HP: {} => {}", &self.stats.hp.unwrap(), &hp));
-                    old_bst.push(self.stats.spa.unwrap());
-                    new_bst.push(hp);
-                }
-                None => {
-                    stats.push(format!("HP: {}", self.stats.hp.unwrap()));
-                    old_bst.push(self.stats.hp.unwrap());
-                    new_bst.push(self.stats.hp.unwrap());
-                }
-            }
-
-            match new_stats.atk {
-                Some(atk) => {
-                    stats.push(format!("Atk: {} => {}", &self.stats.atk.unwrap(), &atk));
-                    old_bst.push(self.stats.atk.unwrap());
-                    new_bst.push(atk);
-                }
-                None => {
-                    stats.push(format!("Atk: {}", self.stats.atk.unwrap()));
-                    old_bst.push(self.stats.atk.unwrap());
-                    new_bst.push(self.stats.atk.unwrap());
-                }
-            }
-
-            match new_stats.def {
-                Some(def) => {
-                    stats.push(format!("Def: {} => {}", &self.stats.def.unwrap(), &def));
-                    old_bst.push(self.stats.def.unwrap());
-                    new_bst.push(def);
-                }
-                None => {
-                    stats.push(format!("Def: {}", self.stats.def.unwrap()));
-                    old_bst.push(self.stats.def.unwrap());
-                    new_bst.push(self.stats.def.unwrap());
-                }
+        display.push(format!("\nLumi version: {}", self.version));
+        for (ability, profile) in self.defensive_profiles() {
+            match ability {
+                Some(ability) => display.push(format!("\nWith {}:\n{}", ability, profile)),
+                None => display.push(format!("\n{}", profile)),
             }
-
-            match new_stats.spa {
-                Some(spa) => {
-                    stats.push(format!("SpA: {} => {}", &self.stats.spa.unwrap(), &spa));
-                    old_bst.push(self.stats.spa.unwrap());
-                    new_bst.push(spa);
-                }
-                None => {
-                    stats.push(format!("SpA: {}", self.stats.spa.unwrap()));
-                    old_bst.push(self.stats.spa.unwrap());
-                    new_bst.push(self.stats.spa.unwrap());
-                }
-            }
-
-            match new_stats.spd {
-                Some(spd) => {
-                    stats.push(format!("SpD: {} => {}", &self.stats.spd.unwrap(), &spd));
-                    old_bst.push(self.stats.spd.unwrap());
-                    new_bst.push(spd);
-                }
-                None => {
-                    stats.push(format!("SpD: {}", self.stats.spd.unwrap()));
-                    old_bst.push(self.stats.spd.unwrap());
-                    new_bst.push(self.stats.spd.unwrap());
-                }
-            }
-
-            match new_stats.spe {
-                Some(spe) => {
-                    stats.push(format!("Spe: {} => {}", &self.stats.spe.unwrap(), &spe));
-                    old_bst.push(self.stats.spe.unwrap());
-                    new_bst.push(spe);
-                }
-                None => {
-                    stats.push(format!("Spe: {}", self.stats.spe.unwrap()));
-                    old_bst.push(self.stats.spe.unwrap());
-                    new_bst.push(self.stats.spe.unwrap());
-                }
-            }
-
-            // sum up base stats
-            let old_sum = old_bst.iter().fold(0, |acc, val| acc + val);
-
-            let new_sum = new_bst.iter().fold(0, |acc, val| acc + val);
-
-            stats.push(format!("BST: {} => {}", old_sum, new_sum));
-
-            display.push(format!("\nStats: {}", stats.join(", ")));
-        } else {
-            display.push(format!("\nStats: {}", self.stats));
         }
+        display.push(format!("\nAbilities: {}", self.abilities));
+        if !self.held_items.is_empty() {
+            display.push(format!(
+                "\nWild Held Items: {}",
+                self.held_items
+                    .iter()
+                    .map(|x| format!("{}", x))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ));
+        }
+        display.push(format!(
+            "\nLocations: \n{}",
+            self.locations
+                .iter()
+                .map(|x| format!("{}", x))
+                .collect::<Vec<String>>()
+                .join("\n")
+        ));
+
+        // the stats changed for Pokemon Luminescent Platinum are written "old => new"
+        display.push(format!("\nStats: {}", StatChanges::of(self)));
 
         display.push(format!(
             "\n\nLevel Up:\n{}",
@@ -181,14 +158,21 @@ impl fmt::Display for Pokemon {
                 .join("\n")
         ));
 
-        display.push(format!("\n\nEgg Moves: \n{}", self.egg_moves.join("\n")));
+        display.push(format!(
+            "\n\nEgg Moves: \n{}",
+            self.egg_moves
+                .iter()
+                .map(|x| format!("{}", x))
+                .collect::<Vec<String>>()
+                .join("\n")
+        ));
 
         // join all display formats and call the write macro
         write!(f, "{}", display.join(""))
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Copy, Clone)]
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, PartialEq)]
 pub struct Stats {
     pub hp: Option<u16>,
     pub atk: Option<u16>,
@@ -205,7 +189,7 @@ impl Stats {
         let fields = [
             &self.hp, &self.atk, &self.def, &self.spa, &self.spd, &self.spe,
         ];
-        if fields.iter().all(|f| f.is_some()) {
+        if fields.iter().all(|f| f.is_none()) {
             None
         } else {
             Some(self)
@@ -275,10 +259,10 @@ impl fmt::Display for Stats {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
 pub struct PokemonTyping {
-    pub type0: String,
-    pub type1: Option<String>,
+    pub type0: PokemonType,
+    pub type1: Option<PokemonType>,
 }
 
 impl fmt::Display for PokemonTyping {
@@ -294,26 +278,384 @@ impl fmt::Display for PokemonTyping {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum AbilitySlot {
+    First,
+    Second,
+    Hidden,
+}
+
+impl fmt::Display for AbilitySlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AbilitySlot::First => write!(f, "Ability 1"),
+            AbilitySlot::Second => write!(f, "Ability 2"),
+            AbilitySlot::Hidden => write!(f, "Hidden ability"),
+        }
+    }
+}
+
+/// The abilities in the order of the dex: "Ability 1/Ability 2/Hidden Ability". Some forms only have
+/// one ability
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(from = "StoredAbilities")]
+pub struct Abilities {
+    pub first: String,
+    pub second: Option<String>,
+    pub hidden: Option<String>,
+}
+
+impl Abilities {
+    pub fn iter(&self) -> impl Iterator<Item = (AbilitySlot, &String)> {
+        [
+            (AbilitySlot::First, Some(&self.first)),
+            (AbilitySlot::Second, self.second.as_ref()),
+            (AbilitySlot::Hidden, self.hidden.as_ref()),
+        ]
+        .into_iter()
+        .filter_map(|(slot, ability)| Some((slot, ability?)))
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.iter().map(|(_, ability)| ability.clone()).collect()
+    }
+
+    /// Every slot holding the ability, the name is compared ignoring case
+    pub fn slots(&self, ability: &str) -> Vec<AbilitySlot> {
+        let ability = ability.trim().to_lowercase();
+        self.iter()
+            .filter(|(_, name)| name.to_lowercase() == ability)
+            .map(|(slot, _)| slot)
+            .collect()
+    }
+}
+
+/// The abilities listed in the order of the dex
+impl From<Vec<String>> for Abilities {
+    fn from(abilities: Vec<String>) -> Self {
+        let mut abilities = abilities.into_iter();
+        Abilities {
+            first: abilities.next().unwrap_or_default(),
+            second: abilities.next(),
+            hidden: abilities.next(),
+        }
+    }
+}
+
+impl fmt::Display for Abilities {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.names().join("/"))
+    }
+}
+
+/// The databases written before the slots were added keep the abilities as a list
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredAbilities {
+    Slots {
+        first: String,
+        second: Option<String>,
+        hidden: Option<String>,
+    },
+    List(Vec<String>),
+}
+
+impl From<StoredAbilities> for Abilities {
+    fn from(abilities: StoredAbilities) -> Self {
+        match abilities {
+            StoredAbilities::Slots { first, second, hidden } => Abilities { first, second, hidden },
+            StoredAbilities::List(abilities) => abilities.into(),
+        }
+    }
+}
+
+/// Item a wild pokemon can be holding and the chance (in percent) of it holding it
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct HeldItem {
+    pub name: String,
+    pub chance: u8,
+}
+
+impl fmt::Display for HeldItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}%)", self.name, self.chance)
+    }
+}
+
+/// Regional and alternate forms, the dex writes the regional ones as a suffix ("Rapidash-G") and
+/// the others after the species ("Wormadam Plant Cloak", "Rotom Heat")
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Form {
+    Alolan,
+    Galarian,
+    Hisuian,
+    PlantCloak,
+    SandyCloak,
+    TrashCloak,
+    Attack,
+    Defense,
+    Speed,
+    Heat,
+    Wash,
+    Frost,
+    Fan,
+    Mow,
+    Altered,
+    Origin,
+    Land,
+    Sky,
+}
+
+impl Form {
+    pub const ALL: [Form; 18] = [
+        Form::Alolan,
+        Form::Galarian,
+        Form::Hisuian,
+        Form::PlantCloak,
+        Form::SandyCloak,
+        Form::TrashCloak,
+        Form::Attack,
+        Form::Defense,
+        Form::Speed,
+        Form::Heat,
+        Form::Wash,
+        Form::Frost,
+        Form::Fan,
+        Form::Mow,
+        Form::Altered,
+        Form::Origin,
+        Form::Land,
+        Form::Sky,
+    ];
+
+    /// Name written after the species in the display name, it's also the one pokemondb uses for
+    /// the form images
+    pub fn name(&self) -> &'static str {
+        match self {
+            Form::Alolan => "Alolan",
+            Form::Galarian => "Galarian",
+            Form::Hisuian => "Hisuian",
+            Form::PlantCloak => "Plant",
+            Form::SandyCloak => "Sandy",
+            Form::TrashCloak => "Trash",
+            Form::Attack => "Attack",
+            Form::Defense => "Defense",
+            Form::Speed => "Speed",
+            Form::Heat => "Heat",
+            Form::Wash => "Wash",
+            Form::Frost => "Frost",
+            Form::Fan => "Fan",
+            Form::Mow => "Mow",
+            Form::Altered => "Altered",
+            Form::Origin => "Origin",
+            Form::Land => "Land",
+            Form::Sky => "Sky",
+        }
+    }
+
+    /// Every spelling of the form accepted when parsing or searching, in lowercase
+    pub fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            Form::Alolan => &["alolan", "alola", "-a"],
+            Form::Galarian => &["galarian", "galar", "-g"],
+            Form::Hisuian => &["hisuian", "hisui", "-h"],
+            Form::PlantCloak => &["plant"],
+            Form::SandyCloak => &["sandy"],
+            Form::TrashCloak => &["trash"],
+            Form::Attack => &["attack"],
+            Form::Defense => &["defense"],
+            Form::Speed => &["speed"],
+            Form::Heat => &["heat"],
+            Form::Wash => &["wash"],
+            Form::Frost => &["frost"],
+            Form::Fan => &["fan"],
+            Form::Mow => &["mow"],
+            Form::Altered => &["altered"],
+            Form::Origin => &["origin"],
+            Form::Land => &["land"],
+            Form::Sky => &["sky"],
+        }
+    }
+
+    /// The species that have the form, None for the regional forms since many species have them
+    pub fn species(&self) -> Option<&'static str> {
+        match self {
+            Form::Alolan | Form::Galarian | Form::Hisuian => None,
+            Form::PlantCloak | Form::SandyCloak | Form::TrashCloak => Some("Wormadam"),
+            Form::Attack | Form::Defense | Form::Speed => Some("Deoxys"),
+            Form::Heat | Form::Wash | Form::Frost | Form::Fan | Form::Mow => Some("Rotom"),
+            Form::Altered | Form::Origin => Some("Giratina"),
+            Form::Land | Form::Sky => Some("Shaymin"),
+        }
+    }
+
+    pub fn is_regional(&self) -> bool {
+        self.species().is_none()
+    }
+}
+
+impl fmt::Display for Form {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Display name of a species and form: ("Rapidash", Some(Galarian)) => "Rapidash Galarian"
+pub fn species_name(base_species: &str, form: Option<Form>) -> String {
+    match form {
+        Some(form) => format!("{} {}", base_species, form.name()),
+        None => base_species.to_string(),
+    }
+}
+
+/// A place, or way, to get the pokemon. The dex lists them as "Route 205 (Honey Tree, 20% chance)"
+/// the area is the text before the parentheses and the method, rate and any other qualifier (time
+/// of day, direction, floor...) are taken from the text inside them. For evolutions the area is the
+/// species it evolves from: "Evolve Charmeleon (Level 36)"
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Encounter {
+    pub area: String,
+    pub method: EncounterMethod,
+    pub rate: Option<u8>,
+    pub qualifiers: Vec<String>,
+}
+
+impl fmt::Display for Encounter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut details = self.qualifiers.clone();
+        match self.method {
+            EncounterMethod::Evolution => write!(f, "Evolve {}", self.area)?,
+            EncounterMethod::Grass | EncounterMethod::Other => write!(f, "{}", self.area)?,
+            method => {
+                write!(f, "{}", self.area)?;
+                details.push(format!("{}", method));
+            }
+        }
+        if let Some(rate) = self.rate {
+            details.push(format!("{}% chance", rate));
+        }
+        if !details.is_empty() {
+            write!(f, " ({})", details.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// How the pokemon is obtained, "Grass" covers every walking encounter (grass, caves, buildings)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EncounterMethod {
+    Grass,
+    Surf,
+    OldRod,
+    GoodRod,
+    SuperRod,
+    Fishing,
+    HoneyTree,
+    Incense,
+    PokeRadar,
+    Static,
+    Gift,
+    EggGift,
+    Fossil,
+    Evolution,
+    Trade,
+    Other,
+}
+
+impl fmt::Display for EncounterMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let method = match self {
+            EncounterMethod::Grass => "Grass",
+            EncounterMethod::Surf => "Surf",
+            EncounterMethod::OldRod => "Old Rod",
+            EncounterMethod::GoodRod => "Good Rod",
+            EncounterMethod::SuperRod => "Super Rod",
+            EncounterMethod::Fishing => "Fishing",
+            EncounterMethod::HoneyTree => "Honey Tree",
+            EncounterMethod::Incense => "Incense",
+            EncounterMethod::PokeRadar => "Poke Radar",
+            EncounterMethod::Static => "Static Encounter",
+            EncounterMethod::Gift => "Gift",
+            EncounterMethod::EggGift => "Egg Gift",
+            EncounterMethod::Fossil => "Fossil Restoration",
+            EncounterMethod::Evolution => "Evolution",
+            EncounterMethod::Trade => "Trade",
+            EncounterMethod::Other => "Other",
+        };
+        write!(f, "{}", method)
+    }
+}
+
+impl EncounterMethod {
+    pub fn is_rod(&self) -> bool {
+        matches!(
+            self,
+            EncounterMethod::OldRod
+                | EncounterMethod::GoodRod
+                | EncounterMethod::SuperRod
+                | EncounterMethod::Fishing
+        )
+    }
+
+    /// Gifts, static encounters, fossils and in-game trades can only be obtained once per save
+    pub fn is_one_time(&self) -> bool {
+        matches!(
+            self,
+            EncounterMethod::Static
+                | EncounterMethod::Gift
+                | EncounterMethod::EggGift
+                | EncounterMethod::Fossil
+                | EncounterMethod::Trade
+        )
+    }
+}
+
+/// The "is_new" flag of the moves marks the moves that were previously unavailable to the
+/// pokemon, the dex lists them with a "(!!)" besides them
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct TM {
     pub num: u16,
     pub name: String,
+    #[serde(default)]
+    pub is_new: bool,
 }
 
 impl fmt::Display for TM {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "TM{} {}", self.num, self.name)
+        write!(f, "TM{} {}{}", self.num, self.name, new_marker(self.is_new))
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct LlvUpMoves {
     pub lvl: u8,
     pub name: String,
+    #[serde(default)]
+    pub is_new: bool,
 }
 
 impl fmt::Display for LlvUpMoves {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "lvl{} {}", self.lvl, self.name)
+        write!(f, "lvl{} {}{}", self.lvl, self.name, new_marker(self.is_new))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EggMove {
+    pub name: String,
+    #[serde(default)]
+    pub is_new: bool,
+}
+
+impl fmt::Display for EggMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.name, new_marker(self.is_new))
+    }
+}
+
+fn new_marker(is_new: bool) -> &'static str {
+    match is_new {
+        true => " (!!)",
+        false => "",
     }
 }
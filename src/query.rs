@@ -0,0 +1,307 @@
+/// This library holds the "PokemonQuery" builder used to search the pokedex by typing, ability, stats
+/// and dex number. The filters use the in game data: the Lumi typing and the Lumi stats
+use std::{fmt, str::FromStr};
+use serde_json::{json, Value};
+
+use crate::{AbilitySlot, Pokemon, PokemonType, Stats};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stat {
+    Hp,
+    Atk,
+    Def,
+    Spa,
+    Spd,
+    Spe,
+    Bst,
+}
+
+impl Stat {
+    pub const ALL: [Stat; 7] = [Stat::Hp, Stat::Atk, Stat::Def, Stat::Spa, Stat::Spd, Stat::Spe, Stat::Bst];
+
+    pub fn value(&self, stats: &Stats) -> Option<u16> {
+        match self {
+            Stat::Hp => stats.hp,
+            Stat::Atk => stats.atk,
+            Stat::Def => stats.def,
+            Stat::Spa => stats.spa,
+            Stat::Spd => stats.spd,
+            Stat::Spe => stats.spe,
+            Stat::Bst => [stats.hp, stats.atk, stats.def, stats.spa, stats.spd, stats.spe]
+                .into_iter()
+                .sum(),
+        }
+    }
+
+    /// The name used by the dex files: "HP", "Atk", "SpA"
+    pub fn short_name(&self) -> &'static str {
+        match self {
+            Stat::Hp => "HP",
+            Stat::Atk => "Atk",
+            Stat::Def => "Def",
+            Stat::Spa => "SpA",
+            Stat::Spd => "SpD",
+            Stat::Spe => "Spe",
+            Stat::Bst => "BST",
+        }
+    }
+
+    /// The stat in SurrealQL, the Lumi stat when it was changed
+    fn surql(&self) -> String {
+        let field = |name: &str| format!("(new_stats.{name} ?? stats.{name})");
+        match self {
+            Stat::Hp => field("hp"),
+            Stat::Atk => field("atk"),
+            Stat::Def => field("def"),
+            Stat::Spa => field("spa"),
+            Stat::Spd => field("spd"),
+            Stat::Spe => field("spe"),
+            Stat::Bst => format!(
+                "math::sum([{}])",
+                ["hp", "atk", "def", "spa", "spd", "spe"].map(field).join(", ")
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Stat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stat::Hp => write!(f, "HP"),
+            Stat::Atk => write!(f, "Attack"),
+            Stat::Def => write!(f, "Defense"),
+            Stat::Spa => write!(f, "Sp. Atk"),
+            Stat::Spd => write!(f, "Sp. Def"),
+            Stat::Spe => write!(f, "Speed"),
+            Stat::Bst => write!(f, "BST"),
+        }
+    }
+}
+
+/// Accepts the short names of the dex: "hp", "atk", "def", "spa", "spd", "spe" and "bst"
+impl FromStr for Stat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "hp" => Ok(Stat::Hp),
+            "atk" | "attack" => Ok(Stat::Atk),
+            "def" | "defense" => Ok(Stat::Def),
+            "spa" | "spatk" => Ok(Stat::Spa),
+            "spd" | "spdef" => Ok(Stat::Spd),
+            "spe" | "speed" => Ok(Stat::Spe),
+            "bst" | "total" => Ok(Stat::Bst),
+            _ => Err(format!("unknown stat \"{}\"", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QuerySort {
+    #[default]
+    Dex,
+    Species,
+    Stat(Stat),
+}
+
+impl FromStr for QuerySort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "dex" => Ok(QuerySort::Dex),
+            "name" | "species" => Ok(QuerySort::Species),
+            stat => Ok(QuerySort::Stat(stat.parse()?)),
+        }
+    }
+}
+
+/// Filters over the pokedex, every filter that is set must match:
+/// "PokemonQuery::new().with_type(PokemonType::Ghost).min(Stat::Spe, 100)"
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PokemonQuery {
+    pub types: Vec<PokemonType>,
+    pub ability: Option<String>,
+    pub hidden_ability: bool,
+    pub min_stats: Vec<(Stat, u16)>,
+    pub max_stats: Vec<(Stat, u16)>,
+    pub min_dex: Option<u32>,
+    pub max_dex: Option<u32>,
+    pub sort: QuerySort,
+    pub descending: bool,
+}
+
+impl PokemonQuery {
+    pub fn new() -> Self {
+        PokemonQuery::default()
+    }
+
+    /// The pokemon must have the type, adding two types asks for both
+    pub fn with_type(mut self, pokemon_type: PokemonType) -> Self {
+        self.types.push(pokemon_type);
+        self
+    }
+
+    pub fn ability(mut self, ability: &str) -> Self {
+        self.ability = Some(ability.to_string());
+        self
+    }
+
+    /// The ability must be the hidden ability of the pokemon
+    pub fn hidden_ability_only(mut self) -> Self {
+        self.hidden_ability = true;
+        self
+    }
+
+    pub fn min(mut self, stat: Stat, value: u16) -> Self {
+        self.min_stats.push((stat, value));
+        self
+    }
+
+    pub fn max(mut self, stat: Stat, value: u16) -> Self {
+        self.max_stats.push((stat, value));
+        self
+    }
+
+    pub fn dex_range(mut self, first: u32, last: u32) -> Self {
+        self.min_dex = Some(first);
+        self.max_dex = Some(last);
+        self
+    }
+
+    pub fn sort_by(mut self, sort: QuerySort, descending: bool) -> Self {
+        self.sort = sort;
+        self.descending = descending;
+        self
+    }
+
+    pub fn matches(&self, pokemon: &Pokemon) -> bool {
+        let typing = pokemon.get_typing();
+        if !self
+            .types
+            .iter()
+            .all(|t| typing.type0 == *t || typing.type1 == Some(*t))
+        {
+            return false;
+        }
+
+        if let Some(ability) = &self.ability {
+            let slots = pokemon.abilities.slots(ability);
+            let found = match self.hidden_ability {
+                true => slots.contains(&AbilitySlot::Hidden),
+                false => !slots.is_empty(),
+            };
+            if !found {
+                return false;
+            }
+        }
+
+        let stats = pokemon.get_stats();
+        let min = self
+            .min_stats
+            .iter()
+            .all(|(stat, min)| stat.value(&stats).is_some_and(|v| v >= *min));
+        let max = self
+            .max_stats
+            .iter()
+            .all(|(stat, max)| stat.value(&stats).is_some_and(|v| v <= *max));
+
+        min && max
+            && self.min_dex.is_none_or(|first| pokemon.dex_num >= first)
+            && self.max_dex.is_none_or(|last| pokemon.dex_num <= last)
+    }
+
+    pub fn sort(&self, pokedex: &mut [Pokemon]) {
+        match self.sort {
+            QuerySort::Dex => pokedex.sort_by_key(|p| p.dex_num),
+            QuerySort::Species => pokedex.sort_by(|a, b| a.species.cmp(&b.species)),
+            QuerySort::Stat(stat) => pokedex.sort_by_key(|p| stat.value(&p.get_stats())),
+        }
+        if self.descending {
+            pokedex.reverse();
+        }
+    }
+
+    /// Filters and sorts the pokedex
+    pub fn apply(&self, pokedex: Vec<Pokemon>) -> Vec<Pokemon> {
+        let mut pokedex: Vec<Pokemon> = pokedex.into_iter().filter(|p| self.matches(p)).collect();
+        self.sort(&mut pokedex);
+        pokedex
+    }
+
+    /// The filters as a SurrealQL condition and the values to bind, "true" when there is no filter.
+    /// The sort order is not part of the condition, it's applied with "sort"
+    pub fn surql_condition(&self) -> (String, Vec<(String, Value)>) {
+        let mut conditions = Vec::new();
+        let mut bindings = Vec::new();
+
+        for (i, pokemon_type) in self.types.iter().enumerate() {
+            conditions.push(format!(
+                "(IF new_pokemon_type THEN $type{i} INSIDE [new_pokemon_type.type0, new_pokemon_type.type1] \
+                 ELSE $type{i} INSIDE [pokemon_type.type0, pokemon_type.type1] END)"
+            ));
+            bindings.push((format!("type{i}"), json!(pokemon_type)));
+        }
+
+        if let Some(ability) = &self.ability {
+            match self.hidden_ability {
+                true => conditions.push(String::from(
+                    "string::lowercase(abilities.hidden ?? '') = string::lowercase($ability)",
+                )),
+                false => conditions.push(String::from(
+                    "string::lowercase($ability) INSIDE [string::lowercase(abilities.first), \
+                     string::lowercase(abilities.second ?? ''), string::lowercase(abilities.hidden ?? '')]",
+                )),
+            }
+            bindings.push((String::from("ability"), json!(ability)));
+        }
+
+        for (i, (stat, min)) in self.min_stats.iter().enumerate() {
+            conditions.push(format!("{} >= $min{i}", stat.surql()));
+            bindings.push((format!("min{i}"), json!(min)));
+        }
+        for (i, (stat, max)) in self.max_stats.iter().enumerate() {
+            conditions.push(format!("{} <= $max{i}", stat.surql()));
+            bindings.push((format!("max{i}"), json!(max)));
+        }
+
+        if let Some(first) = self.min_dex {
+            conditions.push(String::from("dex_num >= $min_dex"));
+            bindings.push((String::from("min_dex"), json!(first)));
+        }
+        if let Some(last) = self.max_dex {
+            conditions.push(String::from("dex_num <= $max_dex"));
+            bindings.push((String::from("max_dex"), json!(last)));
+        }
+
+        if conditions.is_empty() {
+            return (String::from("true"), bindings);
+        }
+        (conditions.join(" AND "), bindings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_pokedex, parser::test_fixtures::*};
+
+    #[test]
+    fn query_filters() {
+        let (_, pokedex) = parse_pokedex(BULBASAUR).unwrap();
+        let bulbasaur = &pokedex[0];
+
+        assert!(PokemonQuery::new().matches(bulbasaur));
+        assert!(PokemonQuery::new().with_type(PokemonType::Poison).with_type(PokemonType::Grass).matches(bulbasaur));
+        assert!(!PokemonQuery::new().with_type(PokemonType::Fire).matches(bulbasaur));
+        assert!(PokemonQuery::new().ability("grassy surge").hidden_ability_only().matches(bulbasaur));
+        assert!(!PokemonQuery::new().ability("Overgrow").hidden_ability_only().matches(bulbasaur));
+        assert!(PokemonQuery::new().min(Stat::Bst, 318).max(Stat::Spe, 45).matches(bulbasaur));
+        assert!(!PokemonQuery::new().min(Stat::Spa, 66).matches(bulbasaur));
+        assert!(!PokemonQuery::new().dex_range(2, 151).matches(bulbasaur));
+
+        let (condition, bindings) = PokemonQuery::new().min(Stat::Spe, 100).surql_condition();
+        assert_eq!(condition, "(new_stats.spe ?? stats.spe) >= $min0");
+        assert_eq!(bindings, [(String::from("min0"), json!(100))]);
+    }
+}
@@ -0,0 +1,140 @@
+/// This library holds the "SearchIndex" used by the autocomplete of the GUI search bar. The names are
+/// read once from the pokedex so typing doesn't query the store
+use std::fmt;
+
+use crate::{closest_matches, Pokemon};
+
+/// What the search bar looks for, "move:" and "ability:" switch from species to moves and abilities
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchKind {
+    Species,
+    Move,
+    Ability,
+}
+
+impl SearchKind {
+    /// Splits the prefix from the search: "move:Surf" => (Move, "Surf")
+    pub fn parse(search: &str) -> (SearchKind, &str) {
+        let search = search.trim();
+        for (kind, prefix) in [(SearchKind::Move, "move:"), (SearchKind::Ability, "ability:")] {
+            // the prefix is ASCII, a search starting with it can be split right after it
+            if search.get(..prefix.len()).is_some_and(|start| start.eq_ignore_ascii_case(prefix)) {
+                return (kind, search[prefix.len()..].trim());
+            }
+        }
+        (SearchKind::Species, search)
+    }
+}
+
+/// A name proposed by the autocomplete
+#[derive(Debug, Clone, PartialEq)]
+pub struct Completion {
+    pub kind: SearchKind,
+    pub name: String,
+}
+
+impl Completion {
+    /// The text written in the search bar when the completion is picked, with the prefix of its kind
+    pub fn text(&self) -> String {
+        match self.kind {
+            SearchKind::Species => self.name.clone(),
+            SearchKind::Move => format!("move:{}", self.name),
+            SearchKind::Ability => format!("ability:{}", self.name),
+        }
+    }
+}
+
+impl fmt::Display for Completion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            SearchKind::Species => write!(f, "{}", self.name),
+            SearchKind::Move => write!(f, "{} (move)", self.name),
+            SearchKind::Ability => write!(f, "{} (ability)", self.name),
+        }
+    }
+}
+
+/// Every species, move and ability name of the pokedex, the moves and abilities are sorted and
+/// without duplicates
+#[derive(Debug, Clone, Default)]
+pub struct SearchIndex {
+    pub species: Vec<String>,
+    pub moves: Vec<String>,
+    pub abilities: Vec<String>,
+}
+
+impl SearchIndex {
+    pub fn new(pokedex: &[Pokemon]) -> Self {
+        let species = pokedex.iter().map(|p| p.species.clone()).collect();
+
+        let mut moves: Vec<String> = pokedex
+            .iter()
+            .flat_map(|p| {
+                p.lvl_up_moves.iter().map(|m| &m.name)
+                    .chain(p.tms.iter().map(|m| &m.name))
+                    .chain(p.egg_moves.iter().map(|m| &m.name))
+            })
+            .cloned()
+            .collect();
+        moves.sort();
+        moves.dedup();
+
+        let mut abilities: Vec<String> = pokedex.iter().flat_map(|p| p.abilities.names()).collect();
+        abilities.sort();
+        abilities.dedup();
+
+        SearchIndex { species, moves, abilities }
+    }
+
+    /// The names closest to the search, best first. The search looks at the species unless it starts
+    /// with "move:" or "ability:"
+    pub fn complete(&self, search: &str, limit: usize) -> Vec<Completion> {
+        let (kind, name) = SearchKind::parse(search);
+        let candidates = match kind {
+            SearchKind::Species => &self.species,
+            SearchKind::Move => &self.moves,
+            SearchKind::Ability => &self.abilities,
+        };
+        closest_matches(name, candidates, limit)
+            .into_iter()
+            .map(|name| Completion { kind, name })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_pokedex, parser::test_fixtures::*};
+
+    #[test]
+    fn completions_by_kind() {
+        let (_, pokedex) = parse_pokedex(&bulbasaur_ivysaur()).unwrap();
+        let index = SearchIndex::new(&pokedex);
+
+        assert_eq!(index.moves, ["Skull Bash", "Tackle", "Toxic"]);
+        assert_eq!(index.abilities, ["Chlorophyll", "Grassy Surge", "Overgrow"]);
+
+        let names = |search: &str| index.complete(search, 5).into_iter().map(|c| c.text()).collect::<Vec<String>>();
+        assert_eq!(names("ivy"), ["Ivysaur"]);
+        assert_eq!(names("move:to"), ["move:Toxic"]);
+        assert_eq!(names("Ability: grass"), ["ability:Grassy Surge"]);
+        assert!(names("move:").is_empty());
+        assert_eq!(SearchKind::parse("MOVE: Surf"), (SearchKind::Move, "Surf"));
+    }
+
+    /// The species with symbols or accents are searched as species, whatever byte the prefix length
+    /// falls on
+    #[test]
+    fn non_ascii_searches() {
+        for search in ["Nidoran♂", "Nidoran♀", "Flabébé", "Farfetch’d", "Pokémon", "♀", "move♂", "abilité:x"] {
+            assert_eq!(SearchKind::parse(search), (SearchKind::Species, search));
+        }
+        assert_eq!(SearchKind::parse("ability:Pokémon"), (SearchKind::Ability, "Pokémon"));
+
+        let index = SearchIndex::new(&dex_files());
+        let names = |search: &str| index.complete(search, 1).into_iter().map(|c| c.text()).collect::<Vec<String>>();
+        assert_eq!(names("Nidoran♂"), ["Nidoran♂"]);
+        assert_eq!(names("Nidoran♀"), ["Nidoran♀"]);
+    }
+}
@@ -0,0 +1,183 @@
+/// This library compares the stats of the original game with the Lumi stats: the change of every stat
+/// and of the BST. The text of the pokemon and the stats of the GUI are both built from it
+use std::fmt;
+
+use crate::{Pokemon, Stat, Stats};
+
+/// The six stats in the order of the dex, without the BST
+pub const BASE_STATS: [Stat; 6] = [Stat::Hp, Stat::Atk, Stat::Def, Stat::Spa, Stat::Spd, Stat::Spe];
+
+/// The old and new value of a stat, they're equal when Lumi didn't change it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatChange {
+    pub stat: Stat,
+    pub old: u16,
+    pub new: u16,
+}
+
+impl StatChange {
+    pub fn delta(&self) -> i32 {
+        i32::from(self.new) - i32::from(self.old)
+    }
+
+    pub fn is_changed(&self) -> bool {
+        self.old != self.new
+    }
+
+    /// The change in percent of the old value, a stat without an old value has no percent change
+    pub fn percent(&self) -> f32 {
+        match self.old {
+            0 => 0.0,
+            old => self.delta() as f32 * 100.0 / f32::from(old),
+        }
+    }
+}
+
+impl fmt::Display for StatChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.is_changed() {
+            true => write!(f, "{}: {} => {}", self.stat.short_name(), self.old, self.new),
+            false => write!(f, "{}: {}", self.stat.short_name(), self.new),
+        }
+    }
+}
+
+/// Every stat of the pokemon in the order of the dex, the stats missing from the dex are left out
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatChanges {
+    pub stats: Vec<StatChange>,
+}
+
+impl StatChanges {
+    /// The stats missing from "new" keep their old value
+    pub fn new(old: &Stats, new: Option<&Stats>) -> Self {
+        let stats = BASE_STATS
+            .iter()
+            .filter_map(|stat| {
+                let old = stat.value(old)?;
+                let new = new.and_then(|new| stat.value(new)).unwrap_or(old);
+                Some(StatChange { stat: *stat, old, new })
+            })
+            .collect();
+        StatChanges { stats }
+    }
+
+    pub fn of(pokemon: &Pokemon) -> Self {
+        StatChanges::new(&pokemon.stats, pokemon.new_stats.as_ref())
+    }
+
+    pub fn get(&self, stat: Stat) -> Option<&StatChange> {
+        self.stats.iter().find(|change| change.stat == stat)
+    }
+
+    pub fn old_bst(&self) -> u16 {
+        self.stats.iter().map(|change| change.old).sum()
+    }
+
+    pub fn new_bst(&self) -> u16 {
+        self.stats.iter().map(|change| change.new).sum()
+    }
+
+    /// The change of the BST, its delta and percent are the ones of the whole stat spread
+    pub fn bst(&self) -> StatChange {
+        StatChange { stat: Stat::Bst, old: self.old_bst(), new: self.new_bst() }
+    }
+
+    /// True when Lumi changed any stat, even if the BST stayed the same
+    pub fn is_changed(&self) -> bool {
+        self.stats.iter().any(|change| change.is_changed())
+    }
+}
+
+/// "HP: 45, Atk: 49 => 60, ..., BST: 318 => 329 (+3.5%)"
+impl fmt::Display for StatChanges {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut stats = self.stats.iter().map(|change| change.to_string()).collect::<Vec<String>>();
+        let bst = self.bst();
+        match bst.is_changed() {
+            true => stats.push(format!("{} ({:+.1}%)", bst, bst.percent())),
+            false => stats.push(bst.to_string()),
+        }
+        write!(f, "{}", stats.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs, path::Path};
+    use crate::parser::*;
+
+    #[test]
+    fn stat_changes() {
+        let example = "006-Charizard\nStats: 78 HP/84 Atk/78 Def/109>110 SpA/85 SpD/100 Spe/534>535 BST\nType: Fire/Flying>Fire/Dragon\nAbilities: Levitate/Blaze/Drought\nLocation:\n* Evolve Charmeleon (Level 36)\nLevel Up:\n1: Dragon Claw\nTMs:\nTM01: Focus Punch\nEgg Moves:\nBelly Drum\n\n019-Rattata-A\nStats: 30>35 HP/56>60 Atk/35>40 Def/25 SpA/35>40 SpD/72>75 Spe/253>275 BST\nType: Dark/Normal\nAbilities: Gluttony/Hustle/Thick Fat\nLocation:\n* Route 201 (Incense, 20% chance)\nLevel Up:\n1: Tackle\nTMs:\nTM06: Toxic\nEgg Moves:\n";
+        let (_, pokedex) = parse_pokedex(example).unwrap();
+
+        let charizard = StatChanges::of(&pokedex[0]);
+        assert_eq!(charizard.get(Stat::Spa), Some(&StatChange { stat: Stat::Spa, old: 109, new: 110 }));
+        assert!(!charizard.get(Stat::Hp).unwrap().is_changed());
+        assert_eq!((charizard.old_bst(), charizard.new_bst()), (534, 535));
+        assert_eq!(
+            charizard.to_string(),
+            "HP: 78, Atk: 84, Def: 78, SpA: 109 => 110, SpD: 85, Spe: 100, BST: 534 => 535 (+0.2%)"
+        );
+
+        let rattata = StatChanges::of(&pokedex[1]);
+        let hp = rattata.get(Stat::Hp).unwrap();
+        assert_eq!((hp.old, hp.new, hp.delta()), (30, 35, 5));
+        assert_eq!(rattata.bst().delta(), 22);
+        assert!((rattata.bst().percent() - 8.7).abs() < 0.05);
+        assert_eq!(StatChanges::new(&pokedex[1].stats, None).to_string(), "HP: 30, Atk: 56, Def: 35, SpA: 25, SpD: 35, Spe: 72, BST: 253");
+    }
+
+    /// The BST computed from the stats of every entry of the dex files matches the BST written in
+    /// the "Stats" line: "534>535 BST". The dex itself has mistakes, the entries below don't add up
+    #[test]
+    fn bst_of_the_dex_files() {
+        let known_errors = [
+            // the printed BST is off
+            "Pidgeot", "Farfetch’d", "Seaking", "Furret", "Ariados", "Magcargo",
+            // "Atk70>/75 Def", the change of the Def can't be read
+            "Blaziken",
+        ];
+        let data_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("pokedex");
+        let mut checked = 0;
+        let mut mismatches = Vec::new();
+        for file in fs::read_dir(data_dir).unwrap() {
+            let path = file.unwrap().path();
+            if path.extension().is_none_or(|extension| extension != "txt") {
+                continue;
+            }
+            let text = fs::read_to_string(&path).unwrap();
+            for entry in entries(&text) {
+                let Ok((_, pokemon)) = pokemon_entry(entry) else {
+                    continue;
+                };
+                let Some(line) = entry.lines().find(|line| line.contains("BST")) else {
+                    continue;
+                };
+                // the value right before "BST", some lines misplace the separator: "Spe405>/450 BST"
+                let printed = line[..line.find("BST").unwrap()].trim_end();
+                let start = printed.rfind(|c: char| !(c.is_ascii_digit() || c == '>' || c == '/')).map_or(0, |i| i + 1);
+                let printed = format!("{} BST", printed[start..].replace('/', ""));
+                let (_, (old, new)) = bst(&printed).unwrap();
+                let old: u16 = old.parse().unwrap();
+                let new: u16 = new.map_or(old, |new| new.parse().unwrap());
+
+                let changes = StatChanges::of(&pokemon);
+                if (changes.old_bst(), changes.new_bst()) != (old, new) {
+                    mismatches.push((pokemon.species.clone(), format!("{} => {} computed, {} => {} printed", changes.old_bst(), changes.new_bst(), old, new)));
+                }
+                checked += 1;
+            }
+        }
+        assert!(checked > 500);
+        let unexpected: Vec<String> = mismatches
+            .iter()
+            .filter(|(species, _)| !known_errors.contains(&species.as_str()))
+            .map(|(species, mismatch)| format!("{}: {}", species, mismatch))
+            .collect();
+        assert!(unexpected.is_empty(), "{}", unexpected.join("\n"));
+        assert_eq!(mismatches.len(), known_errors.len());
+    }
+}
@@ -1,33 +1,30 @@
 use egui::{vec2, Color32, Sense, Pos2, Rect};
 
-use crate::pokemon::Stats;
+use crate::stat_change::StatChanges;
 
 // A wrapper that allows the more idiomatic usage pattern: `ui.add(stats_bar(&stats))`
-pub fn stats_bar(stats: &Stats) -> impl egui::Widget + '_ {
+pub fn stats_bar(stats: &StatChanges) -> impl egui::Widget + '_ {
     move |ui: &mut egui::Ui| stats_bar_ui(ui, stats)
 }
 
-pub fn stats_bar_ui(ui: &mut egui::Ui, stats: &Stats) -> egui::Response {
+/// The bars show the Lumi stats, the stats changed in Lumi are followed by their change and the old
+/// value is shown on hover
+pub fn stats_bar_ui(ui: &mut egui::Ui, stats: &StatChanges) -> egui::Response {
     let rounding = 50.0;
 
     let layout = egui::Layout::top_down(egui::Align::Min);
     let response = ui.allocate_ui_with_layout(ui.available_size(), layout, |ui| {
-        let stats_vec: Vec<(&str, Option<u16>)> = stats.into();
-        for (key, stat) in stats_vec.iter() {
+        for change in stats.stats.iter() {
             ui.horizontal(|ui| {
-                let stat_value = match stat {
-                    Some(value) => value,
-                    // This state should never be reached
-                    None => &10,
-                };
-                let key_label = ui.label(format!("{}", key));
+                let stat_value = change.new;
+                let key_label = ui.label(format!("{}", change.stat));
 
-                let size = vec2((*stat_value as f32) * 1.7, 10.0);
+                let size = vec2((stat_value as f32) * 1.7, 10.0);
                 let color = match stat_value {
-                    val if val >= &150 => Color32::from_rgb(0, 194, 184),
-                    val if val >= &120 => Color32::from_rgb(35, 205, 94),
-                    val if val >= &90 => Color32::from_rgb(160, 229, 21),
-                    val if val >= &60 => Color32::from_rgb(255, 221, 87),
+                    val if val >= 150 => Color32::from_rgb(0, 194, 184),
+                    val if val >= 120 => Color32::from_rgb(35, 205, 94),
+                    val if val >= 90 => Color32::from_rgb(160, 229, 21),
+                    val if val >= 60 => Color32::from_rgb(255, 221, 87),
                     _ => Color32::from_rgb(255, 127, 15),
                 };
 
@@ -43,9 +40,30 @@ pub fn stats_bar_ui(ui: &mut egui::Ui, stats: &Stats) -> egui::Response {
                 );
 
                 ui.label(format!("{}", stat_value));
+                if change.is_changed() {
+                    delta_label(ui, change.delta()).on_hover_text(format!("{} before Lumi", change.old));
+                }
             });
         }
+
+        let bst = stats.bst();
+        ui.horizontal(|ui| {
+            ui.strong(format!("{}: {}", bst.stat, bst.new));
+            if bst.is_changed() {
+                delta_label(ui, bst.delta())
+                    .on_hover_text(format!("{} before Lumi, {:+.1}%", bst.old, bst.percent()));
+            }
+        });
     });
 
     response.response
 }
+
+/// "+5" in green or "-5" in red
+fn delta_label(ui: &mut egui::Ui, delta: i32) -> egui::Response {
+    let color = match delta {
+        delta if delta > 0 => Color32::from_rgb(35, 205, 94),
+        _ => Color32::from_rgb(255, 80, 80),
+    };
+    ui.colored_label(color, format!("{:+}", delta))
+}
@@ -0,0 +1,333 @@
+/// This library holds the "PokedexStore" trait used by the CLI and the GUI to query the pokedex, the
+/// queries don't depend on where the pokedex is kept (SurrealDB or memory)
+use std::sync::{Arc, RwLock};
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::{closest_matches, normalize, DEFAULT_VERSION, Pokemon, PokemonQuery, PokemonType, species_name, parser::*, evolution::*, learnset::*, moves::*, tm::*, ability::*, locations::*};
+
+/// A store can hold several Lumi releases, the queries only look at the pokemon of "version". The
+/// default queries filter "all" in memory, stores with a query language override them
+#[async_trait]
+pub trait PokedexStore: Send + Sync {
+    /// The Lumi release the queries look at
+    fn version(&self) -> &str;
+
+    /// The same store looking at another release
+    fn with_version(&self, version: &str) -> Arc<dyn PokedexStore>;
+
+    /// Every release in the store, sorted
+    async fn versions(&self) -> Result<Vec<String>>;
+
+    /// Every pokemon of the release sorted by dex number
+    async fn all(&self) -> Result<Vec<Pokemon>>;
+
+    /// Adds the pokemon or replaces the one with the same record id, importing twice is harmless
+    async fn insert(&self, pokemon: Pokemon) -> Result<()>;
+
+    /// The move details imported with the release
+    async fn move_details(&self) -> Result<Vec<MoveDetails>>;
+
+    /// Adds the details of a move to the release, replacing the details of the same move
+    async fn insert_move_details(&self, details: MoveDetails) -> Result<()>;
+
+    /// Every move learnt by the pokemon of the release, with its details when they were imported
+    async fn moves(&self) -> Result<MoveRegistry> {
+        Ok(MoveRegistry::new(&self.all().await?).with_details(self.move_details().await?))
+    }
+
+    /// Finds the moves of the type and the category, the moves without details never match a filter
+    async fn find_moves(&self, move_type: Option<PokemonType>, category: Option<MoveCategory>) -> Result<Vec<Move>> {
+        Ok(self.moves().await?.filter(move_type, category))
+    }
+
+    /// Finds the pokemon by species, the forms can be searched by any of their names: "Rapidash-G",
+    /// "Galarian Rapidash" or "Rapidash Galarian". Punctuation is ignored: "Mr Mime", "Farfetchd"
+    async fn find_pokemon(&self, species: &str) -> Result<Option<Pokemon>> {
+        let species = normalize(&canonical_species(species));
+        Ok(self.all().await?.into_iter().find(|p| normalize(&p.species) == species))
+    }
+
+    /// The species of every pokemon of the release
+    async fn species_names(&self) -> Result<Vec<String>> {
+        Ok(self.all().await?.into_iter().map(|p| p.species).collect())
+    }
+
+    /// The species closest to the search, used when "find_pokemon" doesn't find anything
+    async fn suggest(&self, species: &str, limit: usize) -> Result<Vec<String>> {
+        Ok(closest_matches(&canonical_species(species), &self.species_names().await?, limit))
+    }
+
+    /// Finds the pokemon learning the move by level up, TM or as an egg move
+    async fn find_by_move(&self, attack: &str, mode: MoveMatch) -> Result<Vec<Pokemon>> {
+        Ok(self
+            .all()
+            .await?
+            .into_iter()
+            .filter(|p| !learned_moves(p, attack, mode).is_empty())
+            .collect())
+    }
+
+    /// Same search as "find_by_move" with how every pokemon learns the move
+    async fn find_move_learners(&self, attack: &str, mode: MoveMatch) -> Result<Vec<MoveLearner>> {
+        Ok(self
+            .find_by_move(attack, mode)
+            .await?
+            .into_iter()
+            .map(|p| MoveLearner::new(p, attack, mode))
+            .collect())
+    }
+
+    /// The TM table of the release built from the TM lists of the pokemon
+    async fn tm_table(&self) -> Result<TmTable> {
+        Ok(TmTable::new(&self.all().await?))
+    }
+
+    /// The number of the TM, "TM36", "36" or the name of its move: "Sludge Bomb"
+    async fn tm_number(&self, tm: &str) -> Result<Option<u16>> {
+        if let Some(num) = parse_tm_number(tm) {
+            return Ok(Some(num));
+        }
+        Ok(self.tm_table().await?.find(tm).map(|tm| tm.num))
+    }
+
+    /// Finds the pokemon with the TM number in their TM list, the TM can be given by number or by name
+    async fn find_by_tm(&self, tm: &str) -> Result<Vec<Pokemon>> {
+        let Some(num) = self.tm_number(tm).await? else {
+            return Ok(Vec::new());
+        };
+        Ok(self.all().await?.into_iter().filter(|p| p.tms.iter().any(|m| m.num == num)).collect())
+    }
+
+    /// Finds the pokemon that gained the move in Lumi, moves marked with "(!!)" in the dex
+    async fn find_by_new_move(&self, attack: &str) -> Result<Vec<Pokemon>> {
+        Ok(self
+            .all()
+            .await?
+            .into_iter()
+            .filter(|p| learned_moves(p, attack, MoveMatch::Exact).iter().any(|m| m.is_new))
+            .collect())
+    }
+
+    async fn find_by_held_item(&self, item: &str) -> Result<Vec<Pokemon>> {
+        let item = item.to_lowercase();
+        Ok(self
+            .all()
+            .await?
+            .into_iter()
+            .filter(|p| p.held_items.iter().any(|i| i.name.to_lowercase() == item))
+            .collect())
+    }
+
+    /// Finds the pokemon with the type in their Lumi typing
+    async fn find_by_type(&self, pokemon_type: PokemonType) -> Result<Vec<Pokemon>> {
+        Ok(self
+            .all()
+            .await?
+            .into_iter()
+            .filter(|p| {
+                let typing = p.get_typing();
+                typing.type0 == pokemon_type || typing.type1 == Some(pokemon_type)
+            })
+            .collect())
+    }
+
+    /// Finds the pokemon with the ability in any slot and tells which slots hold it
+    async fn find_by_ability(&self, ability: &str) -> Result<Vec<AbilityHolder>> {
+        Ok(self
+            .search(&PokemonQuery::new().ability(ability))
+            .await?
+            .into_iter()
+            .map(|p| AbilityHolder::new(p, ability))
+            .collect())
+    }
+
+    /// The names of every area of the pokedex, sorted
+    async fn area_names(&self) -> Result<Vec<String>> {
+        Ok(areas(&self.all().await?).into_iter().map(|area| area.name).collect())
+    }
+
+    /// The pokemon found in the area and how, the area is matched ignoring case and punctuation
+    async fn find_by_area(&self, area: &str) -> Result<Option<Area>> {
+        Ok(find_area(&self.all().await?, area))
+    }
+
+    /// The gifts, static encounters, fossils and in-game trades of the pokedex grouped by area
+    async fn one_time_encounters(&self) -> Result<Vec<Area>> {
+        Ok(one_time_encounters(&self.all().await?))
+    }
+
+    /// Finds the pokemon matching every filter of the query, sorted as the query asks
+    async fn search(&self, query: &PokemonQuery) -> Result<Vec<Pokemon>> {
+        Ok(query.apply(self.all().await?))
+    }
+
+    /// Builds the whole evolution family of the species
+    async fn find_evolution_chain(&self, species: &str) -> Result<EvolutionChain> {
+        let evolutions = evolutions(&self.all().await?);
+        Ok(evolution_chain(&evolutions, species))
+    }
+}
+
+/// The display name of the species, the form aliases are resolved: "Rapidash-G" => "Rapidash Galarian"
+pub fn canonical_species(species: &str) -> String {
+    let (base_species, form) = species_form(species);
+    species_name(&base_species, form)
+}
+
+/// Pokedex kept in a vector, useful for tests and one-shot runs that don't need a database. The
+/// stores returned by "with_version" share the vector
+pub struct MemoryStore {
+    pokedex: Arc<RwLock<Vec<Pokemon>>>,
+    /// The details of the moves with the release they belong to
+    move_details: Arc<RwLock<Vec<(String, MoveDetails)>>>,
+    version: String,
+}
+
+impl Default for MemoryStore {
+    fn default() -> Self {
+        MemoryStore::new(Vec::new())
+    }
+}
+
+impl MemoryStore {
+    pub fn new(pokedex: Vec<Pokemon>) -> Self {
+        MemoryStore {
+            pokedex: Arc::new(RwLock::new(pokedex)),
+            move_details: Arc::new(RwLock::new(Vec::new())),
+            version: DEFAULT_VERSION.to_string(),
+        }
+    }
+
+    /// Builds the store from the text of a pokedex file
+    pub fn parse(input: &str) -> Result<Self> {
+        let (_, pokedex) = parse_pokedex(input)?;
+        Ok(MemoryStore::new(pokedex))
+    }
+}
+
+#[async_trait]
+impl PokedexStore for MemoryStore {
+    fn version(&self) -> &str {
+        &self.version
+    }
+
+    fn with_version(&self, version: &str) -> Arc<dyn PokedexStore> {
+        Arc::new(MemoryStore {
+            pokedex: self.pokedex.clone(),
+            move_details: self.move_details.clone(),
+            version: version.to_string(),
+        })
+    }
+
+    async fn versions(&self) -> Result<Vec<String>> {
+        let pokedex = self.pokedex.read().map_err(|e| anyhow::anyhow!("{}", e))?;
+        let mut versions: Vec<String> = pokedex.iter().map(|p| p.version.clone()).collect();
+        versions.sort();
+        versions.dedup();
+        Ok(versions)
+    }
+
+    async fn all(&self) -> Result<Vec<Pokemon>> {
+        let pokedex = self.pokedex.read().map_err(|e| anyhow::anyhow!("{}", e))?;
+        let mut pokedex: Vec<Pokemon> = pokedex.iter().filter(|p| p.version == self.version).cloned().collect();
+        pokedex.sort_by_key(|p| p.dex_num);
+        Ok(pokedex)
+    }
+
+    async fn insert(&self, pokemon: Pokemon) -> Result<()> {
+        let mut pokedex = self.pokedex.write().map_err(|e| anyhow::anyhow!("{}", e))?;
+        let id = pokemon.record_id();
+        pokedex.retain(|p| p.record_id() != id);
+        pokedex.push(pokemon);
+        Ok(())
+    }
+
+    async fn move_details(&self) -> Result<Vec<MoveDetails>> {
+        let move_details = self.move_details.read().map_err(|e| anyhow::anyhow!("{}", e))?;
+        Ok(move_details
+            .iter()
+            .filter(|(version, _)| version == &self.version)
+            .map(|(_, details)| details.clone())
+            .collect())
+    }
+
+    async fn insert_move_details(&self, details: MoveDetails) -> Result<()> {
+        let mut move_details = self.move_details.write().map_err(|e| anyhow::anyhow!("{}", e))?;
+        let name = normalize(&details.name);
+        move_details.retain(|(version, d)| version != &self.version || normalize(&d.name) != name);
+        move_details.push((self.version.clone(), details));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parser::test_fixtures::*, AbilitySlot};
+
+    #[tokio::test]
+    async fn memory_store_queries() {
+        // only Bulbasaur gained Toxic in Lumi
+        let example = bulbasaur_ivysaur().replacen("TM06: Toxic", "TM06: Toxic (!!)", 1);
+        let store = MemoryStore::parse(&example).unwrap();
+
+        assert_eq!(store.find_pokemon("ivysaur").await.unwrap().unwrap().dex_num, 2);
+        assert!(store.find_pokemon("Venusaur").await.unwrap().is_none());
+        assert_eq!(store.suggest("Ivysuar", 3).await.unwrap(), ["Ivysaur"]);
+        assert_eq!(store.find_by_move("toxic", MoveMatch::Exact).await.unwrap().len(), 2);
+        assert!(store.find_by_move("tox", MoveMatch::Exact).await.unwrap().is_empty());
+        assert_eq!(store.find_by_move("tox", MoveMatch::Partial).await.unwrap().len(), 2);
+        let learners = store.find_move_learners("Toxic", MoveMatch::Exact).await.unwrap();
+        assert_eq!(learners[0].moves[0].method, MoveLearn::TM(6));
+        assert_eq!(store.find_by_new_move("Toxic").await.unwrap().len(), 1);
+        assert_eq!(store.find_by_type(PokemonType::Poison).await.unwrap().len(), 2);
+        assert!(store.find_by_type(PokemonType::Fire).await.unwrap().is_empty());
+
+        store.insert_move_details(MoveDetails { name: String::from("Toxic"), pp: Some(10), ..Default::default() }).await.unwrap();
+        let moves = store.moves().await.unwrap();
+        assert_eq!(moves.get("toxic").unwrap().details.as_ref().unwrap().pp, Some(10));
+        assert!(moves.get("tackle").unwrap().details.is_none());
+
+        let holders = store.find_by_ability("grassy surge").await.unwrap();
+        assert_eq!(holders.len(), 2);
+        assert_eq!(holders[0].slots, [AbilitySlot::Hidden]);
+        assert_eq!(store.tm_number("toxic").await.unwrap(), Some(6));
+        assert_eq!(store.find_by_tm("TM06").await.unwrap().len(), 2);
+        assert_eq!(store.area_names().await.unwrap(), ["Jubilife City Pokémon Center"]);
+        let area = store.find_by_area("jubilife city pokémon center").await.unwrap().unwrap();
+        assert_eq!(area.encounters[0].species, "Bulbasaur");
+        assert!(store.find_by_area("Bulbasaur").await.unwrap().is_none());
+        assert_eq!(store.one_time_encounters().await.unwrap().len(), 1);
+
+        let chain = store.find_evolution_chain("Bulbasaur").await.unwrap();
+        assert_eq!(chain.stages, [["Bulbasaur"], ["Ivysaur"]]);
+
+        let mut bulbasaur = store.find_pokemon("Bulbasaur").await.unwrap().unwrap();
+        bulbasaur.version = String::from("3.0");
+        store.insert(bulbasaur).await.unwrap();
+        assert_eq!(store.versions().await.unwrap(), [DEFAULT_VERSION, "3.0"]);
+        assert_eq!(store.all().await.unwrap().len(), 2);
+        let next = store.with_version("3.0");
+        assert_eq!(next.all().await.unwrap().len(), 1);
+        assert!(next.find_pokemon("Ivysaur").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn reimport_keeps_one_record() {
+        let pokedex = dex_files();
+        let store = MemoryStore::default();
+        for _ in 0..2 {
+            for pokemon in pokedex.iter().cloned() {
+                store.insert(pokemon).await.unwrap();
+            }
+        }
+        assert_eq!(store.all().await.unwrap().len(), pokedex.len());
+
+        let mut bulbasaur = store.find_pokemon("Bulbasaur").await.unwrap().unwrap();
+        bulbasaur.stats.hp = Some(50);
+        store.insert(bulbasaur).await.unwrap();
+        assert_eq!(store.all().await.unwrap().len(), pokedex.len());
+        assert_eq!(store.find_pokemon("Bulbasaur").await.unwrap().unwrap().stats.hp, Some(50));
+    }
+}
@@ -0,0 +1,182 @@
+/// This library builds the TM table of the release from the TM lists of the pokedex. The dex doesn't
+/// always agree on the move of a TM number, the most common name is kept and the pokemon listing
+/// another move are reported as conflicts
+use std::{collections::BTreeMap, fmt};
+
+use crate::{normalize, Pokemon};
+
+/// "learners" are the species with the TM number in their TM list, in dex order
+#[derive(Debug, Clone, PartialEq)]
+pub struct TmEntry {
+    pub num: u16,
+    pub name: String,
+    pub learners: Vec<String>,
+}
+
+impl fmt::Display for TmEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "TM{:02} {}", self.num, self.name)
+    }
+}
+
+/// Pokemon listing the TM number with another move than the rest of the dex
+#[derive(Debug, Clone, PartialEq)]
+pub struct TmConflict {
+    pub num: u16,
+    pub expected: String,
+    pub found: String,
+    pub species: Vec<String>,
+}
+
+impl fmt::Display for TmConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "TM{:02} is {} but {} for {}",
+            self.num,
+            self.expected,
+            self.found,
+            self.species.join(", ")
+        )
+    }
+}
+
+/// A move listed for a TM number, with every spelling of the move and how often it's used
+struct TmName {
+    key: String,
+    spellings: Vec<(String, usize)>,
+    species: Vec<String>,
+}
+
+impl TmName {
+    /// The most common spelling, the first one found when there is a tie
+    fn name(&self) -> String {
+        let most = self.spellings.iter().map(|(_, count)| *count).max().unwrap_or(0);
+        self.spellings.iter().find(|(_, count)| *count == most).map(|(name, _)| name.clone()).unwrap_or_default()
+    }
+}
+
+/// The TMs sorted by number
+#[derive(Debug, Clone, Default)]
+pub struct TmTable {
+    pub entries: Vec<TmEntry>,
+    pub conflicts: Vec<TmConflict>,
+}
+
+impl TmTable {
+    /// The names spelled differently, "Solarbeam" and "Solar Beam", are the same move and never a
+    /// conflict
+    pub fn new(pokedex: &[Pokemon]) -> Self {
+        // the spellings and the species of every move listed for every TM number, by normalized name
+        let mut names: BTreeMap<u16, Vec<TmName>> = BTreeMap::new();
+        for pokemon in pokedex {
+            for tm in &pokemon.tms {
+                let key = normalize(&tm.name);
+                let tm_names = names.entry(tm.num).or_default();
+                let index = match tm_names.iter().position(|name| name.key == key) {
+                    Some(index) => index,
+                    None => {
+                        tm_names.push(TmName { key, spellings: Vec::new(), species: Vec::new() });
+                        tm_names.len() - 1
+                    }
+                };
+                let tm_name = &mut tm_names[index];
+                match tm_name.spellings.iter_mut().find(|(spelling, _)| spelling == &tm.name) {
+                    Some((_, count)) => *count += 1,
+                    None => tm_name.spellings.push((tm.name.clone(), 1)),
+                }
+                tm_name.species.push(pokemon.species.clone());
+            }
+        }
+
+        let mut table = TmTable::default();
+        for (num, mut tm_names) in names {
+            // the most common move first, the first one found when there is a tie
+            tm_names.sort_by_key(|name| std::cmp::Reverse(name.species.len()));
+            let expected = tm_names[0].name();
+            for tm_name in &tm_names[1..] {
+                table.conflicts.push(TmConflict {
+                    num,
+                    expected: expected.clone(),
+                    found: tm_name.name(),
+                    species: tm_name.species.clone(),
+                });
+            }
+
+            let mut learners: Vec<&String> = tm_names.iter().flat_map(|name| &name.species).collect();
+            learners.sort_by_key(|species| pokedex.iter().position(|p| &&p.species == species));
+            table.entries.push(TmEntry {
+                num,
+                name: expected,
+                learners: learners.into_iter().cloned().collect(),
+            });
+        }
+        table
+    }
+
+    pub fn get(&self, num: u16) -> Option<&TmEntry> {
+        self.entries.iter().find(|tm| tm.num == num)
+    }
+
+    /// Finds the TM by number, "TM36" or "36", or by the name of its move ignoring case and
+    /// punctuation
+    pub fn find(&self, tm: &str) -> Option<&TmEntry> {
+        match parse_tm_number(tm) {
+            Some(num) => self.get(num),
+            None => {
+                let name = normalize(tm);
+                self.entries.iter().find(|tm| normalize(&tm.name) == name)
+            }
+        }
+    }
+}
+
+impl fmt::Display for TmTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries = self.entries.iter().map(|tm| tm.to_string()).collect::<Vec<String>>();
+        write!(f, "{}", entries.join("\n"))?;
+        if !self.conflicts.is_empty() {
+            write!(f, "\n\nConflicts:")?;
+            for conflict in &self.conflicts {
+                write!(f, "\n{}", conflict)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The number of "TM36" or "36", None when it's not a TM number
+pub fn parse_tm_number(tm: &str) -> Option<u16> {
+    let tm = tm.trim();
+    let num = match tm.get(..2) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("tm") => &tm[2..],
+        _ => tm,
+    };
+    num.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::test_fixtures::*;
+
+    #[test]
+    fn table_of_the_dex_files() {
+        let table = TmTable::new(&dex_files());
+
+        assert_eq!(table.get(6).unwrap().learners[..3], ["Bulbasaur", "Ivysaur", "Venusaur"]);
+        // Magcargo lists "TM22: Solarbeam", it's the same move
+        let solar_beam = table.find("tm22").unwrap();
+        assert_eq!(solar_beam.name, "Solar Beam");
+        assert!(solar_beam.learners.contains(&String::from("Magcargo")));
+        assert_eq!(table.find("solarbeam").unwrap().num, 22);
+        assert_eq!(table.find("U-Turn").unwrap().name, "U-turn");
+        assert!(table.find("TM150").is_none());
+
+        assert!(table.conflicts.iter().all(|c| normalize(&c.expected) != normalize(&c.found)));
+        let conflicts: Vec<String> = table.conflicts.iter().map(|c| c.to_string()).collect();
+        assert!(conflicts.contains(&String::from("TM25 is Thunder but Earthquake for Onix")));
+        assert_eq!(parse_tm_number(" TM 36"), Some(36));
+        assert_eq!(parse_tm_number("Surf"), None);
+    }
+}
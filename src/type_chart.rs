@@ -0,0 +1,243 @@
+/// This library holds the pokemon types and the type chart used to compute the weaknesses and
+/// resistances of a pokemon
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::fmt;
+
+use crate::pokemon::PokemonTyping;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PokemonType {
+    Normal,
+    Fire,
+    Water,
+    Electric,
+    Grass,
+    Ice,
+    Fighting,
+    Poison,
+    Ground,
+    Flying,
+    Psychic,
+    Bug,
+    Rock,
+    Ghost,
+    Dragon,
+    Dark,
+    Steel,
+    Fairy,
+}
+
+use PokemonType::*;
+
+impl PokemonType {
+    pub const ALL: [PokemonType; 18] = [
+        Normal, Fire, Water, Electric, Grass, Ice, Fighting, Poison, Ground, Flying, Psychic, Bug,
+        Rock, Ghost, Dragon, Dark, Steel, Fairy,
+    ];
+
+    /// Damage multiplier of an attack of this type against a single defending type
+    pub fn effectiveness(&self, defending: PokemonType) -> f32 {
+        let (super_effective, not_very_effective, no_effect): (&[PokemonType], &[PokemonType], &[PokemonType]) = match self {
+            Normal => (&[], &[Rock, Steel], &[Ghost]),
+            Fire => (&[Grass, Ice, Bug, Steel], &[Fire, Water, Rock, Dragon], &[]),
+            Water => (&[Fire, Ground, Rock], &[Water, Grass, Dragon], &[]),
+            Electric => (&[Water, Flying], &[Electric, Grass, Dragon], &[Ground]),
+            Grass => (&[Water, Ground, Rock], &[Fire, Grass, Poison, Flying, Bug, Dragon, Steel], &[]),
+            Ice => (&[Grass, Ground, Flying, Dragon], &[Fire, Water, Ice, Steel], &[]),
+            Fighting => (&[Normal, Ice, Rock, Dark, Steel], &[Poison, Flying, Psychic, Bug, Fairy], &[Ghost]),
+            Poison => (&[Grass, Fairy], &[Poison, Ground, Rock, Ghost], &[Steel]),
+            Ground => (&[Fire, Electric, Poison, Rock, Steel], &[Grass, Bug], &[Flying]),
+            Flying => (&[Grass, Fighting, Bug], &[Electric, Rock, Steel], &[]),
+            Psychic => (&[Fighting, Poison], &[Psychic, Steel], &[Dark]),
+            Bug => (&[Grass, Psychic, Dark], &[Fire, Fighting, Poison, Flying, Ghost, Steel, Fairy], &[]),
+            Rock => (&[Fire, Ice, Flying, Bug], &[Fighting, Ground, Steel], &[]),
+            Ghost => (&[Psychic, Ghost], &[Dark], &[Normal]),
+            Dragon => (&[Dragon], &[Steel], &[Fairy]),
+            Dark => (&[Psychic, Ghost], &[Fighting, Dark, Fairy], &[]),
+            Steel => (&[Ice, Rock, Fairy], &[Fire, Water, Electric, Steel], &[]),
+            Fairy => (&[Fighting, Dragon, Dark], &[Fire, Poison, Steel], &[]),
+        };
+
+        if super_effective.contains(&defending) {
+            2.0
+        } else if not_very_effective.contains(&defending) {
+            0.5
+        } else if no_effect.contains(&defending) {
+            0.0
+        } else {
+            1.0
+        }
+    }
+}
+
+impl fmt::Display for PokemonType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UnknownType(pub String);
+
+impl fmt::Display for UnknownType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown pokemon type \"{}\"", self.0)
+    }
+}
+
+impl std::error::Error for UnknownType {}
+
+impl FromStr for PokemonType {
+    type Err = UnknownType;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        PokemonType::ALL
+            .iter()
+            .find(|t| t.to_string().eq_ignore_ascii_case(s))
+            .copied()
+            .ok_or_else(|| UnknownType(s.to_string()))
+    }
+}
+
+/// Damage multiplier of every attacking type against a pokemon, in the order of "PokemonType::ALL"
+#[derive(Debug, Clone, PartialEq)]
+pub struct DefensiveProfile {
+    pub multipliers: Vec<(PokemonType, f32)>,
+}
+
+impl DefensiveProfile {
+    pub fn multiplier(&self, attacking: PokemonType) -> f32 {
+        self.multipliers
+            .iter()
+            .find(|(t, _)| *t == attacking)
+            .map(|(_, m)| *m)
+            .unwrap_or(1.0)
+    }
+
+    /// Attacking types that deal exactly "multiplier" times the damage
+    pub fn with_multiplier(&self, multiplier: f32) -> Vec<PokemonType> {
+        self.multipliers
+            .iter()
+            .filter(|(_, m)| *m == multiplier)
+            .map(|(t, _)| *t)
+            .collect()
+    }
+
+    pub fn weaknesses(&self) -> Vec<(PokemonType, f32)> {
+        self.multipliers.iter().filter(|(_, m)| *m > 1.0).copied().collect()
+    }
+
+    pub fn resistances(&self) -> Vec<(PokemonType, f32)> {
+        self.multipliers.iter().filter(|(_, m)| *m < 1.0 && *m > 0.0).copied().collect()
+    }
+
+    pub fn immunities(&self) -> Vec<PokemonType> {
+        self.with_multiplier(0.0)
+    }
+
+    /// Applies the changes an ability makes to the damage taken, only the abilities that change
+    /// the multiplier of a whole type are known
+    pub fn with_ability(mut self, ability: &str) -> Self {
+        let ability = ability.trim().to_lowercase();
+        for (attacking, multiplier) in self.multipliers.iter_mut() {
+            *multiplier = match (ability.as_str(), *attacking) {
+                ("levitate", Ground) | ("earth eater", Ground) => 0.0,
+                ("flash fire", Fire) | ("well-baked body", Fire) => 0.0,
+                ("volt absorb", Electric) | ("lightning rod", Electric) | ("motor drive", Electric) => 0.0,
+                ("water absorb", Water) | ("storm drain", Water) | ("dry skin", Water) => 0.0,
+                ("sap sipper", Grass) => 0.0,
+                ("dry skin", Fire) => *multiplier * 1.25,
+                ("thick fat", Fire) | ("thick fat", Ice) => *multiplier * 0.5,
+                ("heatproof", Fire) | ("water bubble", Fire) => *multiplier * 0.5,
+                ("purifying salt", Ghost) => *multiplier * 0.5,
+                ("wonder guard", _) if *multiplier <= 1.0 => 0.0,
+                ("filter", _) | ("solid rock", _) | ("prism armor", _) if *multiplier > 1.0 => *multiplier * 0.75,
+                _ => *multiplier,
+            };
+        }
+        self
+    }
+}
+
+impl fmt::Display for DefensiveProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |types: Vec<(PokemonType, f32)>| {
+            types
+                .iter()
+                .map(|(t, m)| format!("{} {}x", t, m))
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+        write!(f, "Weaknesses: {}", list(self.weaknesses()))?;
+        write!(f, "\nResistances: {}", list(self.resistances()))?;
+        let immunities = self
+            .immunities()
+            .iter()
+            .map(|t| t.to_string())
+            .collect::<Vec<String>>();
+        write!(f, "\nImmunities: {}", immunities.join(", "))
+    }
+}
+
+impl PokemonTyping {
+    /// Multiplier of every attacking type against this typing
+    pub fn defensive_profile(&self) -> DefensiveProfile {
+        let multipliers = PokemonType::ALL
+            .iter()
+            .map(|attacking| {
+                let multiplier = match self.type1 {
+                    Some(type1) => attacking.effectiveness(self.type0) * attacking.effectiveness(type1),
+                    None => attacking.effectiveness(self.type0),
+                };
+                (*attacking, multiplier)
+            })
+            .collect();
+        DefensiveProfile { multipliers }
+    }
+
+    /// Same as "defensive_profile" including the immunities and resistances given by the ability
+    pub fn defensive_profile_with(&self, ability: &str) -> DefensiveProfile {
+        self.defensive_profile().with_ability(ability)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dual_type_profile() {
+        let typing = PokemonTyping {
+            type0: "Grass".parse().unwrap(),
+            type1: Some("poison".parse().unwrap()),
+        };
+        let profile = typing.defensive_profile();
+        assert_eq!(profile.multiplier(Psychic), 2.0);
+        assert_eq!(profile.multiplier(Grass), 0.25);
+        assert_eq!(profile.multiplier(Fairy), 0.5);
+        assert_eq!(profile.multiplier(Normal), 1.0);
+
+        let gengar = PokemonTyping { type0: Ghost, type1: Some(Poison) };
+        assert_eq!(gengar.defensive_profile().multiplier(Ground), 2.0);
+        assert_eq!(gengar.defensive_profile_with("Levitate").multiplier(Ground), 0.0);
+        assert_eq!(gengar.defensive_profile().immunities(), [Normal, Fighting]);
+
+        assert!("Sound".parse::<PokemonType>().is_err());
+    }
+
+    #[test]
+    fn profiles_with_abilities() {
+        let pokedex = crate::parser::test_fixtures::dex_files();
+        let gengar = pokedex.iter().find(|p| p.species == "Gengar").unwrap();
+        let profiles = gengar.defensive_profiles();
+        assert_eq!(profiles.len(), 2);
+        assert_eq!((profiles[0].0.as_deref(), profiles[0].1.multiplier(Ground)), (None, 2.0));
+        assert_eq!((profiles[1].0.as_deref(), profiles[1].1.multiplier(Ground)), (Some("Levitate"), 0.0));
+        assert!(gengar.to_string().contains("\nWith Levitate:\nWeaknesses: Psychic 2x, Ghost 2x, Dark 2x\n"));
+
+        let bulbasaur = pokedex.iter().find(|p| p.species == "Bulbasaur").unwrap();
+        assert_eq!(bulbasaur.defensive_profiles().len(), 1);
+    }
+}
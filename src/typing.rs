@@ -1,6 +1,7 @@
 use egui::{Color32, RichText, Label};
 
 use crate::pokemon::PokemonTyping;
+use crate::type_chart::{DefensiveProfile, PokemonType};
 
 // A wrapper that allows the more idiomatic usage pattern: `ui.add(typing(&typing))`
 pub fn typing_widget(typing: &PokemonTyping) -> impl egui::Widget + '_ {
@@ -10,43 +11,39 @@ pub fn typing_widget(typing: &PokemonTyping) -> impl egui::Widget + '_ {
 pub fn typing_ui(ui: &mut egui::Ui, typing: &PokemonTyping) -> egui::Response {
     let layout = egui::Layout::left_to_right(egui::Align::Center);
     let response = ui.allocate_ui_with_layout(ui.available_size(), layout, |ui| {
-        ui.add(pokemon_type(typing.type0.clone()));
-        match typing.type1.clone() {
-            Some(type1) => {
-                ui.add(pokemon_type(type1));
-            }
-            None => {}
+        ui.add(pokemon_type(typing.type0));
+        if let Some(type1) = typing.type1 {
+            ui.add(pokemon_type(type1));
         }
     });
 
     response.response
 }
 
-pub fn pokemon_type(pokemon_type: String) -> impl egui::Widget + 'static {
+pub fn pokemon_type(pokemon_type: PokemonType) -> impl egui::Widget + 'static {
     move |ui: &mut egui::Ui| type_ui(ui, pokemon_type)
 }
 
-pub fn type_ui(ui: &mut egui::Ui, pokemon_type: String) -> egui::Response {
-    let color = match pokemon_type.as_str() {
-        "Bug" => {Color32::from_rgb(170, 187, 34)},
-        "Fire" => {Color32::from_rgb(255, 68, 34)},
-        "Dark" => {Color32::from_rgb(119, 85, 68)},
-        "Ice" => {Color32::from_rgb(102, 204, 255)},
-        "Rock" => {Color32::from_rgb(187, 170, 102)},
-        "Grass" => {Color32::from_rgb(119, 204, 85)},
-        "Water" => {Color32::from_rgb(51, 153, 255)},
-        "Fairy" => {Color32::from_rgb(238, 153, 238)},
-        "Ghost" => {Color32::from_rgb(102, 102, 187)},
-        "Steel" => {Color32::from_rgb(170, 170, 187)},
-        "Poison" => {Color32::from_rgb(170, 85, 153)},
-        "Ground" => {Color32::from_rgb(221, 187, 85)},
-        "Normal" => {Color32::from_rgb(170, 170, 153)},
-        "Dragon" => {Color32::from_rgb(119, 102, 238)},
-        "Flying" => {Color32::from_rgb(136, 153, 255)},
-        "Psychic" => {Color32::from_rgb(255, 85, 153)},
-        "Fighting" => {Color32::from_rgb(187, 85, 68)},
-        "Electric" => {Color32::from_rgb(255, 204, 51)},
-        _ => {Color32::TEMPORARY_COLOR},
+pub fn type_ui(ui: &mut egui::Ui, pokemon_type: PokemonType) -> egui::Response {
+    let color = match pokemon_type {
+        PokemonType::Bug => {Color32::from_rgb(170, 187, 34)},
+        PokemonType::Fire => {Color32::from_rgb(255, 68, 34)},
+        PokemonType::Dark => {Color32::from_rgb(119, 85, 68)},
+        PokemonType::Ice => {Color32::from_rgb(102, 204, 255)},
+        PokemonType::Rock => {Color32::from_rgb(187, 170, 102)},
+        PokemonType::Grass => {Color32::from_rgb(119, 204, 85)},
+        PokemonType::Water => {Color32::from_rgb(51, 153, 255)},
+        PokemonType::Fairy => {Color32::from_rgb(238, 153, 238)},
+        PokemonType::Ghost => {Color32::from_rgb(102, 102, 187)},
+        PokemonType::Steel => {Color32::from_rgb(170, 170, 187)},
+        PokemonType::Poison => {Color32::from_rgb(170, 85, 153)},
+        PokemonType::Ground => {Color32::from_rgb(221, 187, 85)},
+        PokemonType::Normal => {Color32::from_rgb(170, 170, 153)},
+        PokemonType::Dragon => {Color32::from_rgb(119, 102, 238)},
+        PokemonType::Flying => {Color32::from_rgb(136, 153, 255)},
+        PokemonType::Psychic => {Color32::from_rgb(255, 85, 153)},
+        PokemonType::Fighting => {Color32::from_rgb(187, 85, 68)},
+        PokemonType::Electric => {Color32::from_rgb(255, 204, 51)},
     };
 
     let (_id, rect) = ui.allocate_space([80.0,30.0].into());
@@ -55,7 +52,43 @@ pub fn type_ui(ui: &mut egui::Ui, pokemon_type: String) -> egui::Response {
         5.0,
         color
     );
-    let response = ui.put(rect, Label::new(RichText::new(pokemon_type.to_uppercase()).color(Color32::BLACK)));
+    let response = ui.put(rect, Label::new(RichText::new(pokemon_type.to_string().to_uppercase()).color(Color32::BLACK)));
 
     response
 }
+
+/// Multipliers shown in the matchup panel and their labels
+const MATCHUP_BUCKETS: [(f32, &str); 6] = [
+    (4.0, "4×"),
+    (2.0, "2×"),
+    (1.0, "1×"),
+    (0.5, "½×"),
+    (0.25, "¼×"),
+    (0.0, "0×"),
+];
+
+// A wrapper that allows the more idiomatic usage pattern: `ui.add(matchups_widget(&profile))`
+pub fn matchups_widget(profile: &DefensiveProfile) -> impl egui::Widget + '_ {
+    move |ui: &mut egui::Ui| matchups_ui(ui, profile)
+}
+
+/// Groups the attacking types by the damage they deal to the pokemon, empty groups are skipped
+pub fn matchups_ui(ui: &mut egui::Ui, profile: &DefensiveProfile) -> egui::Response {
+    let response = egui::Grid::new("matchups").num_columns(2).show(ui, |ui| {
+        for (multiplier, label) in MATCHUP_BUCKETS {
+            let types = profile.with_multiplier(multiplier);
+            if types.is_empty() {
+                continue;
+            }
+            ui.strong(label);
+            ui.horizontal_wrapped(|ui| {
+                for attacking in types {
+                    ui.add(pokemon_type(attacking));
+                }
+            });
+            ui.end_row();
+        }
+    });
+
+    response.response
+}